@@ -0,0 +1,69 @@
+const swc = require('../lib/index');
+const { execFileSync } = require('child_process');
+const fs = require('fs');
+const os = require('os');
+const path = require('path');
+
+const input = path.resolve(__dirname, '../fixtures/merge/input.js');
+const tmp = fs.mkdtempSync(path.join(os.tmpdir(), 'swc-cache-key-'));
+
+afterAll(() => {
+    for (const name of fs.readdirSync(tmp)) {
+        fs.unlinkSync(path.join(tmp, name));
+    }
+    fs.rmdirSync(tmp);
+});
+
+it('should be a sha256 hex string', () => {
+    expect(swc.getCacheKey(input)).toMatch(/^[0-9a-f]{64}$/);
+});
+
+it('should be stable across processes', () => {
+    const script = `console.log(require(${JSON.stringify(path.resolve(__dirname, '../lib/index'))})` +
+        `.getCacheKey(${JSON.stringify(input)}))`;
+    const key = execFileSync(process.execPath, ['-e', script]).toString().trim();
+
+    expect(key).toBe(swc.getCacheKey(input));
+});
+
+it('should change if options change', () => {
+    expect(swc.getCacheKey(input, { jsc: { target: 'es5' } })).not.toBe(swc.getCacheKey(input));
+    expect(swc.getCacheKey(input, { sourceMaps: true })).not.toBe(swc.getCacheKey(input));
+});
+
+it('should change if contents of a config file change', () => {
+    const filename = path.join(tmp, 'input.js');
+    fs.writeFileSync(path.join(tmp, '.swcrc'), '{}');
+    const before = swc.getCacheKey(filename, { root: tmp });
+
+    // Same config, different content
+    fs.writeFileSync(path.join(tmp, '.swcrc'), '{ }');
+    swc.clearConfigCache();
+
+    expect(swc.getCacheKey(filename, { root: tmp })).not.toBe(before);
+});
+
+it('should change if inlined environment variables change', () => {
+    const options = { jsc: { transform: { optimizer: { globals: { vars: {} } } } } };
+    const env = process.env.NODE_ENV;
+
+    try {
+        process.env.NODE_ENV = 'production';
+        const production = swc.getCacheKey(input, options);
+        process.env.NODE_ENV = 'development';
+        const development = swc.getCacheKey(input, options);
+
+        expect(production).not.toBe(development);
+        // Not inlined without the optimizer
+        process.env.NODE_ENV = 'production';
+        const key = swc.getCacheKey(input);
+        process.env.NODE_ENV = 'development';
+        expect(swc.getCacheKey(input)).toBe(key);
+    } finally {
+        if (env === undefined) {
+            delete process.env.NODE_ENV;
+        } else {
+            process.env.NODE_ENV = env;
+        }
+    }
+});
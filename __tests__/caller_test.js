@@ -0,0 +1,46 @@
+const swc = require('../lib/index');
+
+const src = `import foo from './foo';
+export const bar = () => import('./bar');`;
+
+it('should apply module config by default', () => {
+    const out = swc.transformSync(`import foo from './foo';`, {
+        module: { type: 'commonjs' },
+    });
+
+    expect(out.code).toContain("require('./foo')");
+});
+
+it('should keep es modules if the caller supports them', () => {
+    const out = swc.transformSync(src, {
+        caller: { name: 'bundler', supportsStaticESM: true, supportsDynamicImport: true },
+        module: { type: 'commonjs' },
+    });
+
+    expect(out.code).toContain('export const bar');
+    expect(out.code).toMatch(/import foo from ['"]\.\/foo['"]/);
+    expect(out.code).toMatch(/import\(['"]\.\/bar['"]\)/);
+    expect(out.code).not.toContain('require(');
+});
+
+it('should parse import() if the caller supports it', () => {
+    const out = swc.transformSync(`export const bar = () => import('./bar');`, {
+        caller: { name: 'bundler', supportsDynamicImport: true },
+    });
+
+    expect(out.code).toMatch(/import\(['"]\.\/bar['"]\)/);
+});
+
+it('should skip the module pass in loadOptions', () => {
+    const options = { module: { type: 'commonjs' } };
+
+    expect(swc.loadOptions('input.js', options).passes).toContain('modules::common_js');
+    expect(swc.loadOptions('input.js', Object.assign({
+        caller: { name: 'bundler', supportsStaticESM: true },
+    }, options)).passes).not.toContain('modules::common_js');
+});
+
+it('should affect cache keys', () => {
+    expect(swc.getCacheKey('input.js', { caller: { name: 'bundler', supportsDynamicImport: true } }))
+        .not.toBe(swc.getCacheKey('input.js', { caller: { name: 'bundler' } }));
+});
@@ -0,0 +1,96 @@
+const swc = require('../lib/index');
+const fs = require('fs');
+const os = require('os');
+const path = require('path');
+
+const es2018 = JSON.stringify({ jsc: { target: 'es2018' } });
+const es5 = JSON.stringify({ jsc: { target: 'es5' } });
+
+let dir;
+beforeEach(() => {
+    dir = fs.mkdtempSync(path.join(os.tmpdir(), 'swc-cache-'));
+    fs.writeFileSync(path.join(dir, 'input.js'), 'export const foo = () => 1;');
+});
+
+afterEach(() => {
+    for (const f of fs.readdirSync(dir)) {
+        fs.unlinkSync(path.join(dir, f));
+    }
+    fs.rmdirSync(dir);
+});
+
+/**
+ * Writes a file and bumps its mtime, as mtime may have a coarse resolution.
+ */
+function write(file, content, time) {
+    fs.writeFileSync(file, content);
+    fs.utimesSync(file, time, time);
+}
+
+it('should reload modified .swcrc', () => {
+    const compiler = new swc.Compiler();
+    const swcrc = path.join(dir, '.swcrc');
+    const input = path.join(dir, 'input.js');
+
+    write(swcrc, es2018, 1000);
+    expect(compiler.transformFileSync(input, { root: dir }).code).toContain('=>');
+
+    write(swcrc, es5, 2000);
+    expect(compiler.transformFileSync(input, { root: dir }).code).not.toContain('=>');
+});
+
+it('should load newly created .swcrc', () => {
+    const compiler = new swc.Compiler();
+    const input = path.join(dir, 'input.js');
+
+    expect(compiler.transformFileSync(input, { root: dir }).code).not.toContain('=>');
+
+    write(path.join(dir, '.swcrc'), es2018, 1000);
+    expect(compiler.transformFileSync(input, { root: dir }).code).toContain('=>');
+});
+
+it('should load newly created package.json', () => {
+    const compiler = new swc.Compiler();
+    const input = path.join(dir, 'input.js');
+
+    expect(compiler.transformFileSync(input, { root: dir }).code).not.toContain('=>');
+
+    write(path.join(dir, 'package.json'), JSON.stringify({ swc: JSON.parse(es2018) }), 1000);
+    expect(compiler.transformFileSync(input, { root: dir }).code).toContain('=>');
+});
+
+it('should inline current values of envs', () => {
+    const compiler = new swc.Compiler();
+    const options = {
+        swcrc: false,
+        jsc: { transform: { optimizer: { globals: { envs: ['SWC_CACHE_TEST'] } } } },
+    };
+    const transform = () => compiler.transformSync('export const v = process.env.SWC_CACHE_TEST;', options).code;
+
+    try {
+        process.env.SWC_CACHE_TEST = 'first';
+        expect(transform()).toMatch(/['"]first['"]/);
+        expect(transform()).toMatch(/['"]first['"]/);
+
+        process.env.SWC_CACHE_TEST = 'second';
+        expect(transform()).toMatch(/['"]second['"]/);
+    } finally {
+        delete process.env.SWC_CACHE_TEST;
+    }
+});
+
+it('should not share entries between options', () => {
+    const compiler = new swc.Compiler();
+    const input = path.join(dir, 'input.js');
+    write(path.join(dir, '.swcrc'), es2018, 1000);
+
+    expect(compiler.transformFileSync(input, { root: dir }).code).toContain('=>');
+    expect(compiler.transformFileSync(input, { root: dir, swcrc: false }).code).not.toContain('=>');
+});
+
+it('can be cleared', () => {
+    const compiler = new swc.Compiler();
+
+    expect(() => compiler.clearConfigCache()).not.toThrow();
+    expect(() => swc.clearConfigCache()).not.toThrow();
+});
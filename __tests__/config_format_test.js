@@ -0,0 +1,33 @@
+const swc = require('../lib/index');
+const path = require('path');
+
+const fixture = (...names) => path.resolve(__dirname, '../fixtures', ...names);
+
+it('should accept comments and trailing commas', () => {
+    const out = swc.transformFileSync(fixture('jsonc/input.js'));
+
+    expect(out.code).toContain('=>');
+    expect(out.code).toContain('http://example.com/*');
+});
+
+it('should accept JSON5', () => {
+    const out = swc.transformFileSync(fixture('json5/input.js'));
+
+    expect(out.code).toContain('=>');
+    expect(out.code.trim()).not.toContain('\n');
+});
+
+it('should reject unknown keys in JSON5', () => {
+    expect.assertions(6);
+
+    try {
+        swc.transformSync('', { filename: 'input.js', configFile: fixture('json5/unknown-key.json5') });
+    } catch (err) {
+        expect(err.code).toBe(swc.codes.ERR_SWC_CONFIG_PARSE);
+        expect(err.jsonPath).toBe('jsc.taget');
+        expect(err.message).toContain('at `jsc.taget` (line 3, column 5)');
+        expect(err.message).toContain('did you mean `target`?');
+        expect(err.cause.line).toBe(3);
+        expect(err.cause.column).toBe(5);
+    }
+});
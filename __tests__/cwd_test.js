@@ -0,0 +1,59 @@
+const swc = require('../lib/index');
+const path = require('path');
+
+const monorepo = path.resolve(__dirname, '../fixtures/monorepo');
+const isolated = path.resolve(__dirname, '../fixtures/isolated');
+
+it('should read relative filenames from cwd', () => {
+    const out = swc.transformFileSync('packages/b/input.js', { cwd: monorepo, configFile: false });
+
+    expect(out.code).toContain('=>');
+});
+
+it('should read relative filenames from cwd asynchronously', async () => {
+    const out = await swc.transformFile('packages/b/input.js', { cwd: monorepo, configFile: false });
+
+    expect(out.code).toContain('=>');
+});
+
+it('should resolve a relative cwd from process.cwd()', () => {
+    const cwd = path.relative(process.cwd(), monorepo);
+    const out = swc.transformFileSync('packages/b/input.js', { cwd, configFile: false });
+
+    expect(out.code).toContain('=>');
+});
+
+it('should resolve configFile from cwd', () => {
+    const out = swc.transformFileSync('src/input.js', {
+        cwd: isolated,
+        configFile: 'swc.config.json',
+        swcrc: false,
+    });
+
+    expect(out.code.trim()).not.toContain('\n');
+});
+
+it('should write relative filenames into source maps', () => {
+    const out = swc.transformFileSync('packages/b/input.js', {
+        cwd: monorepo,
+        configFile: false,
+        sourceMaps: true,
+    });
+
+    expect(JSON.parse(out.map).sources).toEqual(['packages/b/input.js']);
+});
+
+it('should normalize filenames in both sync and async apis', async () => {
+    const options = { cwd: monorepo, configFile: false, sourceMaps: true };
+
+    const sync = swc.transformFileSync('./packages/b/input.js', options);
+    const async = await swc.transformFile('./packages/b/input.js', options);
+    expect(JSON.parse(async.map).sources).toEqual(JSON.parse(sync.map).sources);
+    expect(JSON.parse(sync.map).sources).toEqual(['packages/b/input.js']);
+
+    const filename = (f) => f.then(() => undefined, err => err.filename);
+    const syncFilename = await filename(Promise.resolve().then(() => swc.transformFileSync('./packages/b/missing.js', options)));
+    const asyncFilename = await filename(swc.transformFile('./packages/b/missing.js', options));
+    expect(asyncFilename).toBe(syncFilename);
+    expect(syncFilename).toBe('packages/b/missing.js');
+});
@@ -0,0 +1,25 @@
+const swc = require('../lib/index');
+const path = require('path');
+
+const input = path.resolve(__dirname, '../fixtures/env/input.js');
+
+it('should ignore env sections which do not match', () => {
+    const out = swc.transformFileSync(input, { envName: 'development' });
+
+    expect(out.code).not.toContain('=>');
+    expect(out.code).toContain('\n');
+});
+
+it('should merge the matching env section', () => {
+    expect(swc.transformFileSync(input, { envName: 'test' }).code).toContain('=>');
+    expect(swc.transformFileSync(input, { envName: 'production' }).code.trim()).not.toContain('\n');
+});
+
+it('should merge env of programmatic options', () => {
+    const out = swc.transformFileSync(input, {
+        envName: 'development',
+        env: { development: { jsc: { target: 'es2018' } } },
+    });
+
+    expect(out.code).toContain('=>');
+});
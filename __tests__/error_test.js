@@ -0,0 +1,88 @@
+const swc = require('../lib/index');
+const path = require('path');
+
+it('should export error codes', () => {
+    expect(swc.codes.ERR_SWC_PARSE).toBe('ERR_SWC_PARSE');
+    expect(swc.codes.ERR_SWC_CONFIG_PARSE).toBe('ERR_SWC_CONFIG_PARSE');
+});
+
+it('should attach path and cause of invalid .swcrc', () => {
+    const dir = path.resolve(__dirname, '../fixtures/invalid-swcrc');
+    expect.assertions(4);
+
+    try {
+        swc.transformFileSync(path.join(dir, 'input.js'));
+    } catch (err) {
+        expect(err.code).toBe(swc.codes.ERR_SWC_CONFIG_PARSE);
+        expect(err.path).toBe(path.join(dir, '.swcrc'));
+        expect(err.cause).toBeInstanceOf(Error);
+        expect(err.cause.line).toBe(3);
+    }
+});
+
+it('should attach cause of io errors', () => {
+    expect.assertions(2);
+
+    try {
+        swc.transformFileSync(__dirname + '/does-not-exist.js');
+    } catch (err) {
+        expect(err.code).toBe(swc.codes.ERR_SWC_READ_MODULE);
+        expect(err.cause.kind).toBe('NotFound');
+    }
+});
+
+describe('panic', () => {
+    const options = {
+        filename: 'input.js',
+        jsc: {
+            transform: {
+                optimizer: {
+                    globals: {
+                        vars: {
+                            __DEBUG__: '('
+                        },
+                    }
+                }
+            }
+        }
+    };
+
+    it('should be thrown as an error', () => {
+        expect.assertions(3);
+
+        try {
+            swc.transformSync(`__DEBUG__`, options);
+        } catch (err) {
+            expect(err.code).toBe(swc.codes.ERR_SWC_PANIC);
+            expect(err.filename).toBe('input.js');
+            expect(err.message).toContain('failed to parse global variable');
+        }
+    });
+
+    it('should reject the promise', async () => {
+        await expect(swc.transform(`__DEBUG__`, options)).rejects.toHaveProperty('code', 'ERR_SWC_PANIC');
+    });
+
+    it('should not break the compiler', () => {
+        try {
+            swc.transformSync(`__DEBUG__`, options);
+        } catch (err) { }
+
+        expect(swc.transformSync(`foo.default`).code.trim()).toBe(`foo['default'];`);
+    });
+});
+
+it('should report json path of unknown keys in .swcrc', () => {
+    const dir = path.resolve(__dirname, '../fixtures/unknown-key');
+    expect.assertions(5);
+
+    try {
+        swc.transformFileSync(path.join(dir, 'input.js'));
+    } catch (err) {
+        expect(err.path).toBe(path.join(dir, '.swcrc'));
+        expect(err.jsonPath).toBe('jsc.transfrom');
+        expect(err.validKeys).toContain('transform');
+        expect(err.message).toContain('did you mean `transform`?');
+        expect(err.cause.line).toBe(3);
+    }
+});
@@ -0,0 +1,79 @@
+const swc = require('../lib/index');
+const path = require('path');
+
+const fixture = (...names) => path.resolve(__dirname, '../fixtures', ...names);
+
+it('should merge extended config file', () => {
+    const out = swc.transformFileSync(fixture('extends/pkg/input.js'));
+
+    expect(out.code).toContain('=>');
+    expect(out.code.trim()).not.toContain('\n');
+});
+
+it('should resolve packages from node_modules', () => {
+    const out = swc.transformFileSync(fixture('extends/shared/pkg/input.js'));
+
+    expect(out.code).toContain('=>');
+});
+
+it('should detect cycles', () => {
+    expect.assertions(3);
+
+    try {
+        swc.transformFileSync(fixture('extends-cycle/input.js'));
+    } catch (err) {
+        expect(err.code).toBe(swc.codes.ERR_SWC_CONFIG_EXTENDS_CYCLE);
+        expect(err.extendsChain).toEqual([
+            fixture('extends-cycle/.swcrc'),
+            fixture('extends-cycle/a.json'),
+            fixture('extends-cycle/b.json'),
+            fixture('extends-cycle/a.json'),
+        ]);
+        expect(err.message).toContain(' -> ');
+    }
+});
+
+it('should report the extends chain of errors in extended files', () => {
+    expect.assertions(4);
+
+    try {
+        swc.transformFileSync(fixture('extends-invalid/input.js'));
+    } catch (err) {
+        expect(err.code).toBe(swc.codes.ERR_SWC_CONFIG_PARSE);
+        expect(err.path).toBe(fixture('extends-invalid/base.json'));
+        expect(err.jsonPath).toBe('jsc.target');
+        expect(err.extendsChain).toEqual([
+            fixture('extends-invalid/.swcrc'),
+            fixture('extends-invalid/base.json'),
+        ]);
+    }
+});
+
+it('should throw if extended file is not found', () => {
+    expect.assertions(2);
+
+    try {
+        swc.transformFileSync(fixture('extends-missing/input.js'));
+    } catch (err) {
+        expect(err.code).toBe(swc.codes.ERR_SWC_CONFIG_EXTENDS_NOT_FOUND);
+        expect(err.path).toBe(fixture('extends-missing/.swcrc'));
+    }
+});
+
+it('should keep patterns of the extending config', () => {
+    const out = swc.transformFileSync(fixture('extends-test/pkg/input.js'));
+
+    expect(out.code).toContain('=>');
+    expect(out.code.trim()).not.toContain('\n');
+});
+
+it('should resolve inline extends from cwd', () => {
+    const out = swc.transformSync('export const foo = () => 1;', {
+        cwd: fixture('extends'),
+        filename: 'input.js',
+        swcrc: false,
+        extends: './base.json',
+    });
+
+    expect(out.code).toContain('=>');
+});
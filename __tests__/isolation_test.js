@@ -0,0 +1,37 @@
+const swc = require('../lib/index');
+const path = require('path');
+
+const dir = path.resolve(__dirname, '../fixtures/isolated');
+const input = path.join(dir, 'src/input.js');
+
+it('should load all config files by default', () => {
+    const out = swc.transformFileSync(input, { root: dir });
+
+    expect(out.code).toContain('=>');
+    expect(out.code).toContain('exports.foo');
+    expect(out.code.trim()).not.toContain('\n');
+});
+
+it('should use only inline options if configFile and swcrc are false', () => {
+    const out = swc.transformFileSync(input, { root: dir, configFile: false, swcrc: false });
+
+    expect(out.code).not.toContain('=>');
+    expect(out.code).toContain('export const');
+    expect(out.code.trim()).toContain('\n');
+});
+
+it('should search cwd for swc.config.json if configFile is true', () => {
+    const options = { cwd: dir, root: 'empty', swcrc: false };
+
+    expect(swc.transformFileSync(input, options).code.trim()).toContain('\n');
+    expect(swc.transformFileSync(input, Object.assign({ configFile: true }, options)).code.trim())
+        .not.toContain('\n');
+});
+
+it('should not load swc.config.json of cwd by default unless root is given', () => {
+    const options = { cwd: dir, swcrc: false };
+
+    expect(swc.transformFileSync(input, options).code.trim()).toContain('\n');
+    expect(swc.transformFileSync(input, Object.assign({ root: '.' }, options)).code.trim())
+        .not.toContain('\n');
+});
@@ -0,0 +1,46 @@
+const swc = require('../lib/index');
+const path = require('path');
+
+const fixtures = path.resolve(__dirname, '../fixtures');
+
+it('should return the merged config', () => {
+    const input = path.join(fixtures, 'merge/input.js');
+    const out = swc.loadOptions(input, { jsc: { target: 'es5' } });
+
+    expect(out.config.jsc.target).toBe('es5');
+    expect(out.config.jsc.externalHelpers).toBe(true);
+    expect(out.config.minify).toBe(true);
+    expect(out.files).toEqual([path.join(fixtures, 'merge/.swcrc')]);
+});
+
+it('should list config files from the lowest precedence', () => {
+    const out = swc.loadOptions(path.join(fixtures, 'extends/pkg/input.js'));
+
+    expect(out.files).toEqual([
+        path.join(fixtures, 'extends/base.json'),
+        path.join(fixtures, 'extends/pkg/.swcrc'),
+    ]);
+});
+
+it('should list enabled passes', () => {
+    const input = path.join(fixtures, 'merge/input.js');
+
+    const es2017 = swc.loadOptions(input).passes;
+    expect(es2017).toContain('react');
+    expect(es2017).toContain('compat::es2018');
+    expect(es2017).not.toContain('compat::es2017');
+    expect(es2017).not.toContain('typescript::strip');
+
+    const es5 = swc.loadOptions(input, {
+        jsc: { target: 'es5' },
+        module: { type: 'commonjs' },
+    }).passes;
+    expect(es5).toContain('compat::es2015');
+    expect(es5).toContain('modules::common_js');
+});
+
+it('should not require the file to exist', () => {
+    const out = swc.loadOptions(path.join(fixtures, 'merge/missing.js'));
+
+    expect(out.files).toEqual([path.join(fixtures, 'merge/.swcrc')]);
+});
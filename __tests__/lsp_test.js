@@ -0,0 +1,98 @@
+const swc = require('../lib/index');
+const { createServer, uriToPath } = require('../lib/lsp');
+const { PassThrough } = require('stream');
+
+it('should emit diagnostics of the language server protocol', () => {
+    const compiler = new swc.Compiler({ diagnosticsFormat: 'lsp' });
+    expect.assertions(5);
+
+    try {
+        compiler.parseSync(`let a = 1;\n  class Foo {`);
+    } catch (err) {
+        const d = err.diagnostics[0];
+
+        expect(d.source).toBe('swc');
+        expect(d.severity).toBe(1);
+        expect(d.code).toBe('ERR_SWC_PARSE');
+        expect(d.range.start.line).toBe(1);
+        expect(d.range.start.character).toBeGreaterThan(0);
+    }
+});
+
+it('should count characters in utf-16 code units', () => {
+    const compiler = new swc.Compiler({ diagnosticsFormat: 'lsp' });
+    expect.assertions(1);
+
+    try {
+        compiler.parseSync(`'😀' class`);
+    } catch (err) {
+        // `😀` is 2 code units
+        expect(err.diagnostics[0].range.start.character).toBe(5);
+    }
+});
+
+it('should publish diagnostics over stdio', (done) => {
+    const input = new PassThrough();
+    const output = new PassThrough();
+    createServer(input, output);
+
+    const send = (message) => {
+        const body = JSON.stringify(Object.assign({ jsonrpc: '2.0' }, message));
+        input.write(`Content-Length: ${Buffer.byteLength(body)}\r\n\r\n${body}`);
+    };
+
+    let received = '';
+    output.on('data', (chunk) => {
+        received += chunk.toString();
+        if (!received.includes('publishDiagnostics')) return;
+
+        const body = received.slice(received.lastIndexOf('\r\n\r\n') + 4);
+        const message = JSON.parse(body);
+        expect(message.params.uri).toBe(`file://${__dirname}/input.js`);
+        expect(message.params.diagnostics[0].source).toBe('swc');
+        done();
+    });
+
+    send({ id: 1, method: 'initialize', params: {} });
+    send({
+        method: 'textDocument/didOpen',
+        params: {
+            textDocument: {
+                uri: `file://${__dirname}/input.js`,
+                languageId: 'javascript',
+                version: 1,
+                text: 'class Foo {',
+            },
+        },
+    });
+});
+
+it('should convert file uris to paths', () => {
+    expect(uriToPath('file:///home/a%20b/input.js', 'linux')).toBe('/home/a b/input.js');
+    expect(uriToPath('file:///c%3A/a/input.js', 'win32')).toBe('c:\\a\\input.js');
+    expect(uriToPath('file://server/share/input.js', 'win32')).toBe('\\\\server\\share\\input.js');
+    expect(uriToPath('untitled:Untitled-1', 'linux')).toBe('untitled:Untitled-1');
+});
+
+it('should reply with a parse error to invalid json', (done) => {
+    const input = new PassThrough();
+    const output = new PassThrough();
+    createServer(input, output);
+
+    let received = '';
+    output.on('data', (chunk) => {
+        received += chunk.toString();
+        const headerEnd = received.indexOf('\r\n\r\n');
+        if (headerEnd === -1) return;
+        const length = parseInt(/Content-Length: *(\d+)/.exec(received)[1], 10);
+        const body = received.slice(headerEnd + 4);
+        if (Buffer.byteLength(body) < length) return;
+
+        const message = JSON.parse(body);
+        expect(message.id).toBe(null);
+        expect(message.error.code).toBe(-32700);
+        done();
+    });
+
+    input.write('Content-Length: 5\r\n\r\n{oops');
+});
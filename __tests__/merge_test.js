@@ -0,0 +1,109 @@
+const swc = require('../lib/index');
+const path = require('path');
+
+const input = path.resolve(__dirname, '../fixtures/merge/input.js');
+
+it('should use the config file if inline options are not set', () => {
+    const out = swc.transformFileSync(input);
+
+    expect(out.code).toContain('=>');
+    expect(out.code).toContain('@swc/helpers');
+    expect(out.code.trim()).not.toContain('\n');
+});
+
+it('should override scalars with inline options', () => {
+    const out = swc.transformFileSync(input, {
+        jsc: { target: 'es5', externalHelpers: false },
+        minify: false,
+    });
+
+    expect(out.code).not.toContain('=>');
+    expect(out.code).not.toContain('@swc/helpers');
+    expect(out.code).toContain('function _classCallCheck');
+    expect(out.code.trim()).toContain('\n');
+});
+
+it('should merge react options field by field', () => {
+    const out = swc.transformFileSync(input, {
+        jsc: { transform: { react: { pragmaFrag: 'Frag' } } },
+        minify: false,
+    });
+
+    expect(out.code).toContain('h(Frag');
+    expect(out.code).toMatch(/h\(['"]div['"]/);
+});
+
+it('should merge optimizer.globals.vars by key', () => {
+    const out = swc.transformFileSync(input, {
+        jsc: { transform: { optimizer: { globals: { vars: { __B__: "'c'" } } } } },
+        minify: false,
+    });
+
+    expect(out.code).toMatch(/a = ['"]a['"]/);
+    expect(out.code).toMatch(/b = ['"]c['"]/);
+});
+
+it('should merge constModules.globals by module and name', () => {
+    const out = swc.transformFileSync(input, {
+        jsc: {
+            transform: {
+                constModules: { globals: { '@ember/env-flags': { TRACE: 'false' } } },
+            },
+        },
+        minify: false,
+    });
+
+    expect(out.code).toMatch(/flags = \[\s*true,\s*false\s*\]/);
+});
+
+describe('precedence', () => {
+    const fixture = (...names) => path.resolve(__dirname, '../fixtures', ...names);
+
+    it('should prefer inline options to env of config files', () => {
+        const out = swc.transformFileSync(fixture('env/input.js'), {
+            envName: 'test',
+            jsc: { target: 'es5' },
+        });
+
+        expect(out.code).not.toContain('=>');
+    });
+
+    it('should prefer inline options to overrides of config files', () => {
+        const out = swc.transformFileSync(fixture('overrides/input.ts'), {
+            jsc: { target: 'es5' },
+        });
+
+        expect(out.code).not.toContain('=>');
+    });
+});
+
+describe('replaced values', () => {
+    const input = path.resolve(__dirname, '../fixtures/merge-replace/input.js');
+
+    it('should replace envs as a whole', () => {
+        const { config } = swc.loadOptions(input, {
+            jsc: { transform: { optimizer: { globals: { envs: ['FOO'] } } } },
+        });
+
+        expect(config.jsc.transform.optimizer.globals.envs).toEqual(['FOO']);
+    });
+
+    it('should replace parser as a whole', () => {
+        const { config, passes } = swc.loadOptions(input, {
+            jsc: { parser: { syntax: 'ecmascript' } },
+        });
+
+        expect(config.jsc.parser.jsx).toBeFalsy();
+        expect(passes).not.toContain('react');
+    });
+
+    it('should replace module as a whole', () => {
+        const { config, passes } = swc.loadOptions(input, {
+            module: { type: 'commonjs' },
+        });
+
+        expect(config.module.type).toBe('commonjs');
+        expect(config.module.globals).toBeUndefined();
+        expect(passes).toContain('modules::common_js');
+    });
+});
@@ -0,0 +1,68 @@
+const swc = require('../lib/index');
+const path = require('path');
+
+const fixture = (...names) => path.resolve(__dirname, '../fixtures', ...names);
+
+describe('overrides', () => {
+    it('should select parser by extension', () => {
+        const ts = swc.transformFileSync(fixture('overrides/input.ts'));
+        expect(ts.code).not.toContain('number');
+
+        const tsx = swc.transformFileSync(fixture('overrides/input.tsx'));
+        expect(tsx.code).toContain('React.createElement');
+    });
+
+    it('should merge matching entries in order', () => {
+        expect(swc.transformFileSync(fixture('overrides/input.ts')).code).toContain('=>');
+        expect(swc.transformFileSync(fixture('overrides/input.tsx')).code).not.toContain('=>');
+        expect(swc.transformFileSync(fixture('overrides/input.js')).code).not.toContain('=>');
+    });
+
+    it('should resolve patterns of programmatic options relative to cwd', () => {
+        const out = swc.transformSync('export const foo = () => 1;', {
+            filename: 'src/input.js',
+            swcrc: false,
+            overrides: [{ test: 'src', jsc: { target: 'es2018' } }],
+        });
+
+        expect(out.code).toContain('=>');
+    });
+
+    it('should apply env and overrides nested in sections', () => {
+        const transform = (envName, env, overrides) => swc.transformSync('export const foo = () => 1;', {
+            filename: 'src/input.js',
+            swcrc: false,
+            envName,
+            env,
+            overrides,
+        }).code;
+        const es2018 = { jsc: { target: 'es2018' } };
+
+        // `env` and `overrides` in an override
+        expect(transform('test', {}, [{ test: 'src', env: { test: es2018 } }])).toContain('=>');
+        expect(transform('production', {}, [{ test: 'src', env: { test: es2018 } }])).not.toContain('=>');
+        expect(transform('test', {}, [{ test: 'src', overrides: [Object.assign({ test: 'src' }, es2018)] }])).toContain('=>');
+        expect(transform('test', {}, [{ test: 'src', overrides: [Object.assign({ test: 'lib' }, es2018)] }])).not.toContain('=>');
+
+        // `env` and `overrides` in an env section
+        expect(transform('test', { test: { env: { test: es2018 } } }, [])).toContain('=>');
+        expect(transform('test', { test: { overrides: [Object.assign({ test: 'src' }, es2018)] } }, [])).toContain('=>');
+    });
+});
+
+describe('exclude', () => {
+    it('should ignore .swcrc for excluded files', () => {
+        expect(swc.transformFileSync(fixture('exclude/input.js')).code).toContain('=>');
+        expect(swc.transformFileSync(fixture('exclude/vendor/input.js')).code).not.toContain('=>');
+    });
+});
+
+it('should throw on invalid patterns', () => {
+    expect.assertions(1);
+
+    try {
+        swc.transformSync('', { filename: 'input.js', overrides: [{ test: 'src/[' }] });
+    } catch (err) {
+        expect(err.code).toBe(swc.codes.ERR_SWC_PATTERN);
+    }
+});
@@ -0,0 +1,40 @@
+const swc = require('../lib/index');
+const path = require('path');
+
+const fixture = (...parts) => path.resolve(__dirname, '../fixtures', ...parts);
+
+it('should use the "swc" key of package.json', () => {
+    expect(swc.transformFileSync(fixture('package-json/input.js')).code).toContain('=>');
+});
+
+it('should prefer .swcrc in the same directory', () => {
+    expect(swc.transformFileSync(fixture('package-swcrc/input.js')).code).not.toContain('=>');
+});
+
+it('should stop at the package boundary', () => {
+    expect(swc.transformFileSync(fixture('package-json/nested/input.js')).code).not.toContain('=>');
+});
+
+it('should ignore package.json if swcrc is false', () => {
+    const out = swc.transformFileSync(fixture('package-json/input.js'), { swcrc: false });
+
+    expect(out.code).not.toContain('=>');
+});
+
+it('should use "type": "commonjs" as the default module type', () => {
+    expect(swc.transformFileSync(fixture('package-json/input.js')).code).toContain('export const');
+    expect(swc.transformFileSync(fixture('package-type/input.js')).code).toContain('exports.foo');
+});
+
+it('should keep es modules if "type" is "module"', () => {
+    const out = swc.transformFileSync(fixture('package-type/esm/input.js'));
+
+    expect(out.code).toContain('export const');
+    expect(out.code).not.toContain('exports.foo');
+});
+
+it('should prefer module of programmatic options over "type"', () => {
+    const out = swc.transformFileSync(fixture('package-type/input.js'), { module: { type: 'amd' } });
+
+    expect(out.code).toContain('define(');
+});
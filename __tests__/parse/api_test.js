@@ -23,3 +23,90 @@ it('can work synchronously', () => {
     expect(m.body[0].type).toBe(`ClassDeclaration`);
 });
 
+
+it('should reject with diagnostics', async () => {
+    expect.assertions(4);
+
+    try {
+        await swc.parse(`class Foo {`);
+    } catch (err) {
+        expect(err.diagnostics).toHaveLength(1);
+        expect(err.diagnostics[0].severity).toBe('error');
+        expect(err.diagnostics[0].code).toBe('ERR_SWC_PARSE');
+        expect(err.diagnostics[0].span.start.line).toBe(1);
+    }
+});
+
+it('should render code frame without colors', () => {
+    const compiler = new swc.Compiler({ color: 'never', codeFrameLines: 0 });
+    expect.assertions(2);
+
+    expect(() => compiler.parseSync(`let a = 1;\nclass Foo {`)).toThrow(/> 2 \| class Foo {/);
+    try {
+        compiler.parseSync(`class Foo {`);
+    } catch (err) {
+        expect(err.message).not.toContain('\u001b[');
+    }
+});
+
+it('should omit code frame if disabled', () => {
+    const compiler = new swc.Compiler({ codeFrame: false });
+    expect.assertions(2);
+
+    try {
+        compiler.parseSync(`class Foo {`);
+    } catch (err) {
+        expect(err.diagnostics[0].codeFrame).toBeUndefined();
+        expect(err.message).toContain('|');
+    }
+});
+
+it('should omit code frame of messages if disabled', () => {
+    const compiler = new swc.Compiler({ messageCodeFrame: false });
+    expect.assertions(2);
+
+    try {
+        compiler.parseSync(`class Foo {`);
+    } catch (err) {
+        expect(err.diagnostics[0].codeFrame).toContain('|');
+        expect(err.message).not.toContain('|');
+    }
+});
+
+it('should recover from errors if requested', () => {
+    const { module, diagnostics } = swc.parseSync(`class A {}\nlet = ;\nclass B {}`, { recover: true });
+
+    expect(module.type).toBe(`Module`);
+    expect(module.body.map(item => item.type)).toEqual([
+        `ClassDeclaration`,
+        `EmptyStatement`,
+        `ClassDeclaration`,
+    ]);
+    expect(diagnostics.length).toBeGreaterThan(0);
+    expect(diagnostics[0].span.start.line).toBe(2);
+});
+
+it('should tell error nodes apart from empty statements', () => {
+    const src = `class A {}\nlet = ;\n;`;
+    const { module, skipped } = swc.parseSync(src, { recover: true });
+
+    expect(skipped).toHaveLength(1);
+    expect(src.slice(skipped[0].start - module.span.start, skipped[0].end - module.span.start)).toBe(`let = ;`);
+
+    const isErrorNode = item => skipped.some(s => s.start === item.span.start && s.end === item.span.end);
+    expect(module.body.map(item => [item.type, isErrorNode(item)])).toEqual([
+        [`ClassDeclaration`, false],
+        [`EmptyStatement`, true],
+        [`EmptyStatement`, false],
+    ]);
+});
+
+it('should return items before the erroneous part if it gives up', () => {
+    const src = `class A {}\n` + `let = ;\n`.repeat(100);
+    const { module, diagnostics } = swc.parseSync(src, { recover: true });
+
+    expect(module.body[0].type).toBe(`ClassDeclaration`);
+    expect(module.body.slice(1).every(item => item.type === `EmptyStatement`)).toBe(true);
+    expect(diagnostics.length).toBeGreaterThan(0);
+    expect(diagnostics[0].span.start.line).toBe(2);
+});
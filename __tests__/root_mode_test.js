@@ -0,0 +1,49 @@
+const swc = require('../lib/index');
+const path = require('path');
+
+const monorepo = path.resolve(__dirname, '../fixtures/monorepo');
+const pkg = path.join(monorepo, 'packages/a');
+const input = path.join(pkg, 'input.js');
+
+it('should not search upward by default', () => {
+    const out = swc.transformFileSync(input, { root: pkg });
+
+    expect(out.code).not.toContain('=>');
+});
+
+it('should use swc.config.json of the root', () => {
+    const out = swc.transformFileSync(input, { root: monorepo });
+
+    expect(out.code).toContain('=>');
+});
+
+it('should find swc.config.json if rootMode is upward', () => {
+    const out = swc.transformFileSync(input, { root: pkg, rootMode: 'upward' });
+
+    expect(out.code).toContain('=>');
+});
+
+it('should ignore swc.config.json if configFile is false', () => {
+    const out = swc.transformFileSync(input, { root: pkg, rootMode: 'upward', configFile: false });
+
+    expect(out.code).not.toContain('=>');
+});
+
+it('should throw if swc.config.json is not found', () => {
+    const root = path.resolve(__dirname, '../fixtures/invalid-swcrc');
+    expect.assertions(2);
+
+    try {
+        swc.transformFileSync(input, { root, rootMode: 'upward' });
+    } catch (err) {
+        expect(err.code).toBe(swc.codes.ERR_SWC_ROOT_CONFIG_NOT_FOUND);
+        expect(err.message).toContain('swc.config.json');
+    }
+});
+
+it('should fall back to root if rootMode is upward-optional', () => {
+    const root = path.resolve(__dirname, '../fixtures/invalid-swcrc');
+    const out = swc.transformFileSync(input, { root, rootMode: 'upward-optional' });
+
+    expect(out.code).not.toContain('=>');
+});
@@ -0,0 +1,171 @@
+const swc = require('../lib/index');
+const native = require('../native');
+const fs = require('fs');
+const path = require('path');
+
+const schema = native.configSchema();
+const root = path.resolve(__dirname, '..');
+
+/**
+ * Returns schemas which should all be satisfied, resolving `$ref` and `allOf`.
+ */
+function parts(s) {
+    if (s.$ref) {
+        return parts(schema.definitions[s.$ref.replace('#/definitions/', '')]);
+    }
+    return [s].concat(...(s.allOf || []).map(parts));
+}
+
+function typeOf(value) {
+    if (value === null) return 'null';
+    if (Array.isArray(value)) return 'array';
+    if (typeof value === 'number') return Number.isInteger(value) ? 'integer' : 'number';
+    return typeof value;
+}
+
+/**
+ * A minimal validator, which supports keywords used by the schema.
+ */
+function validate(s, value) {
+    if (typeof s === 'boolean') return s;
+
+    const all = parts(s);
+    for (const p of all) {
+        if (p.anyOf && !p.anyOf.some(s => validate(s, value))) return false;
+        if (p.oneOf && !p.oneOf.some(s => validate(s, value))) return false;
+        if (p.enum && !p.enum.includes(value)) return false;
+        if (p.type && ![].concat(p.type).some(t => t === typeOf(value) || (t === 'number' && typeOf(value) === 'integer'))) {
+            return false;
+        }
+        if (p.items && Array.isArray(value) && !value.every(v => validate(p.items, v))) return false;
+    }
+
+    if (typeOf(value) === 'object') {
+        const objects = all.filter(p => p.properties || p.additionalProperties !== undefined);
+        for (const key of Object.keys(value)) {
+            const known = objects.filter(p => p.properties && p.properties[key]);
+            if (known.length) {
+                if (!known.every(p => validate(p.properties[key], value[key]))) return false;
+            } else if (objects.length && objects.every(p => p.additionalProperties === false)) {
+                return false;
+            } else if (!objects.every(p => p.additionalProperties === undefined || validate(p.additionalProperties, value[key]))) {
+                return false;
+            }
+        }
+    }
+
+    return true;
+}
+
+/**
+ * Returns a value for each branch of `s`, with all properties set.
+ */
+function samples(s) {
+    if (s.$ref) {
+        return samples(schema.definitions[s.$ref.replace('#/definitions/', '')]);
+    }
+    if (s.anyOf) {
+        return [].concat(...s.anyOf.filter(s => s.type !== 'null').map(samples));
+    }
+    if (s.enum) {
+        return [s.enum[0]];
+    }
+
+    const object = {};
+    for (const part of s.allOf || []) {
+        Object.assign(object, samples(part).pop());
+    }
+    for (const key of Object.keys(s.properties || {})) {
+        object[key] = samples(s.properties[key])[0];
+    }
+    if (Object.keys(object).length) {
+        return [object];
+    }
+
+    switch ([].concat(s.type)[0]) {
+        case 'boolean': return [true];
+        case 'string': return ['a'];
+        case 'array': return [samples(s.items)];
+        case 'object': return [typeof s.additionalProperties === 'object' ? { a: samples(s.additionalProperties)[0] } : {}];
+        default: throw new Error(`unexpected schema: ${JSON.stringify(s)}`);
+    }
+}
+
+function configFiles(dir) {
+    return fs.readdirSync(dir).reduce((files, name) => {
+        const file = path.join(dir, name);
+        if (fs.statSync(file).isDirectory()) {
+            return files.concat(configFiles(file));
+        }
+        if (name === '.swcrc' || name === 'swc.config.json') {
+            return files.concat([file]);
+        }
+        return files;
+    }, []);
+}
+
+it('should match the committed schema', () => {
+    const { file, render } = require('../scripts/generate-schema');
+
+    // Run `npm run schema` if this fails.
+    expect(fs.readFileSync(file, 'utf8')).toBe(render());
+});
+
+it('should accept valid config files', () => {
+    const invalid = ['invalid-swcrc', 'unknown-key', 'extends-invalid'];
+    const files = ['fixtures', 'issue-225', 'issue-226', 'issue-351', 'issue-389']
+        .reduce((files, dir) => files.concat(configFiles(path.join(root, dir))), [])
+        .filter(file => !invalid.some(dir => file.includes(`${path.sep}${dir}${path.sep}`)))
+        // JSON5 is not supported by JSON.parse()
+        .filter(file => !file.includes(`${path.sep}json5${path.sep}`) && !file.includes(`${path.sep}jsonc${path.sep}`));
+
+    expect(files.length).toBeGreaterThan(10);
+    for (const file of files) {
+        expect({ file, valid: validate(schema, JSON.parse(fs.readFileSync(file, 'utf8'))) })
+            .toEqual({ file, valid: true });
+    }
+});
+
+it('should reject invalid config files', () => {
+    for (const dir of ['invalid-swcrc', 'unknown-key']) {
+        const file = path.join(root, 'fixtures', dir, '.swcrc');
+
+        expect(validate(schema, JSON.parse(fs.readFileSync(file, 'utf8')))).toBe(false);
+    }
+});
+
+it('should describe targets and module types', () => {
+    const config = {
+        $schema: './node_modules/@swc/core/lib/swcrc.schema.json',
+        jsc: { target: 'es2017', parser: { syntax: 'typescript', tsx: true } },
+        module: { type: 'umd', globals: { react: 'React' } },
+    };
+
+    expect(validate(schema, config)).toBe(true);
+    expect(validate(schema, { module: { type: 'esm' } })).toBe(false);
+    expect(validate(schema, { jsc: { target: 'es6' } })).toBe(false);
+});
+
+// Types of swc are mirrored for the schema, so they are checked by passing
+// samples through the real types.
+it('should mirror parser configs of swc', () => {
+    const parsers = samples(schema.definitions.Syntax);
+
+    expect(parsers).toHaveLength(2);
+    for (const parser of parsers) {
+        const { config } = swc.loadOptions('input.js', { configFile: false, swcrc: false, jsc: { parser } });
+
+        expect(config.jsc.parser).toEqual(parser);
+    }
+});
+
+it('should mirror module configs of swc', () => {
+    const modules = samples(schema.definitions.ModuleConfig);
+
+    expect(modules).toHaveLength(3);
+    for (const module of modules) {
+        const { config } = swc.loadOptions('input.js', { configFile: false, swcrc: false, module });
+
+        expect(config.module).toEqual(module);
+    }
+});
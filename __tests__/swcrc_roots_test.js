@@ -0,0 +1,48 @@
+const swc = require('../lib/index');
+const path = require('path');
+
+const root = path.resolve(__dirname, '../fixtures/monorepo');
+const b = path.join(root, 'packages/b/input.js');
+const c = path.join(root, 'packages/c/input.js');
+const nested = path.join(root, 'packages/group/d/input.js');
+
+// swc.config.json of the fixture is disabled to see if .swcrc is applied.
+const transform = (file, swcrcRoots) => swc.transformFileSync(file, { cwd: root, configFile: false, swcrcRoots });
+
+it('should load .swcrc of all packages by default', () => {
+    expect(transform(b).code).toContain('=>');
+    expect(transform(c).code).toContain('=>');
+});
+
+it('should load .swcrc only from listed packages', () => {
+    expect(transform(b, 'packages/b').code).toContain('=>');
+    expect(transform(c, 'packages/b').code).not.toContain('=>');
+});
+
+it('should support globs', () => {
+    expect(transform(b, ['packages/*']).code).toContain('=>');
+    expect(transform(c, ['packages/*']).code).toContain('=>');
+    expect(transform(c, ['.', 'packages/b*']).code).not.toContain('=>');
+});
+
+it('should not match nested directories with *', () => {
+    expect(transform(nested).code).toContain('=>');
+    expect(transform(nested, ['packages/*']).code).not.toContain('=>');
+    expect(transform(nested, ['packages/*/*']).code).toContain('=>');
+    expect(transform(nested, ['packages/**']).code).toContain('=>');
+});
+
+it('should support booleans', () => {
+    expect(transform(b, true).code).toContain('=>');
+    expect(transform(b, false).code).not.toContain('=>');
+});
+
+it('should throw on invalid globs', () => {
+    expect.assertions(1);
+
+    try {
+        transform(b, 'packages/[');
+    } catch (err) {
+        expect(err.code).toBe(swc.codes.ERR_SWC_SWCRC_ROOTS);
+    }
+});
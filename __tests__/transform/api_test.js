@@ -78,3 +78,69 @@ it("should handle comments in arrow expression", () => {
 
   expect(out.code).toContain(`return true`);
 });
+
+it("should have warnings", async () => {
+  const out = await swc.transform(`foo.default`, {});
+  expect(out.warnings).toEqual([]);
+
+  expect(swc.transformSync(`foo.default`).warnings).toEqual([]);
+});
+
+it("should report warnings with their location", () => {
+  const out = swc.transformSync(`let a = 1;\nlet b = process.env.SWC_UNSET_ENV;`, {
+    filename: "input.js",
+    swcrc: false,
+    jsc: {
+      transform: { optimizer: { globals: { envs: ["SWC_UNSET_ENV"] } } }
+    }
+  });
+
+  expect(out.code).toContain(`process.env.SWC_UNSET_ENV`);
+  expect(out.warnings.length).toBe(1);
+
+  const warning = out.warnings[0];
+  expect(warning.severity).toBe(`warning`);
+  expect(warning.message).toContain(`process.env.SWC_UNSET_ENV`);
+  expect(warning.span.start).toEqual({ line: 2, column: 8 });
+  expect(warning.span.end).toEqual({ line: 2, column: 33 });
+});
+
+it("should warn only about unset envs of the global process", () => {
+  const globals = envs => ({
+    filename: "input.js",
+    swcrc: false,
+    jsc: { transform: { optimizer: { globals: { envs } } } }
+  });
+
+  expect(swc.transformSync(`process.env.SWC_UNSET_ENV`, globals(undefined)).warnings).toEqual([]);
+  expect(
+    swc.transformSync(
+      `const process = { env: {} };\nprocess.env.SWC_UNSET_ENV;`,
+      globals(["SWC_UNSET_ENV"])
+    ).warnings
+  ).toEqual([]);
+  expect(swc.loadOptions("input.js", globals(["SWC_UNSET_ENV"])).passes).not.toContain("unset_envs");
+});
+
+it("should throw instead of panicking on invalid input", () => {
+  expect.assertions(2);
+
+  try {
+    swc.transformSync(`class Foo {`, { filename: "input.js" });
+  } catch (err) {
+    expect(err.code).toBe("ERR_SWC_PARSE");
+    expect(err.filename).toBe("input.js");
+  }
+});
+
+it("should throw if file does not exist", () => {
+  const path = __dirname + "/does-not-exist.js";
+  expect.assertions(2);
+
+  try {
+    swc.transformFileSync(path);
+  } catch (err) {
+    expect(err.code).toBe("ERR_SWC_READ_MODULE");
+    expect(err.filename).toBe(path);
+  }
+});
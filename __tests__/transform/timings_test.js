@@ -0,0 +1,55 @@
+const swc = require("../../lib/index");
+const fs = require("fs");
+const os = require("os");
+const path = require("path");
+
+it("should not report timings by default", () => {
+  const out = swc.transformSync("class Foo {}");
+
+  expect(out.timings).toBeUndefined();
+});
+
+it("should report timings of each phase", () => {
+  const out = swc.transformSync("class Foo {}", {
+    timings: true,
+    sourceMaps: true
+  });
+  const names = out.timings.map(p => p.name);
+
+  expect(names[0]).toBe("resolveConfig");
+  expect(names[1]).toBe("parse");
+  expect(names).toContain("pass:resolver");
+  expect(names).toContain("pass:compat::es2015");
+  expect(names).toContain("pass:hygiene");
+  expect(names).toContain("pass:fixer");
+  expect(names.slice(-2)).toEqual(["codegen", "sourceMap"]);
+  for (const phase of out.timings) {
+    expect(phase.duration).toBeGreaterThanOrEqual(0);
+  }
+});
+
+it("should skip disabled passes", () => {
+  const out = swc.transformSync("class Foo {}", {
+    timings: true,
+    jsc: { target: "es2018" }
+  });
+  const names = out.timings.map(p => p.name);
+
+  expect(names).not.toContain("pass:compat::es2015");
+  expect(names).not.toContain("pass:react");
+});
+
+it("should write chrome trace", () => {
+  const traceFile = path.join(os.tmpdir(), `swc-trace-${process.pid}.json`);
+  swc.transformSync("class Foo {}", { filename: "input.js", traceFile });
+
+  const trace = JSON.parse(fs.readFileSync(traceFile, "utf8"));
+  fs.unlinkSync(traceFile);
+
+  expect(trace.traceEvents[0].name).toBe("transform");
+  expect(trace.traceEvents[0].args.filename).toBe("input.js");
+  expect(trace.traceEvents.map(e => e.name)).toContain("pass:hygiene");
+  for (const e of trace.traceEvents) {
+    expect(e.ph).toBe("X");
+  }
+});
@@ -0,0 +1,9 @@
+import { DEBUG, TRACE } from '@ember/env-flags';
+
+export const a = __A__;
+export const b = __B__;
+export const flags = [DEBUG, TRACE];
+export const el = <><div /></>;
+export class Foo {}
+export const foo = () => 1;
+export const spread = { ...flags };
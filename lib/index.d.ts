@@ -2,11 +2,15 @@
 declare module "@swc/core" {
 
     export class Compiler {
-        constructor();
+        constructor(options?: CompilerOptions);
 
+        parse(src: string, options: ParseOptions & { recover: true }): Promise<RecoveredModule>;
         parse(src: string, options?: ParseOptions): Promise<Module>;
+        parseSync(src: string, options: ParseOptions & { recover: true }): RecoveredModule;
         parseSync(src: string, options?: ParseOptions): Module;
+        parseFile(path: string, options: ParseOptions & { recover: true }): Promise<RecoveredModule>;
         parseFile(path: string, options?: ParseOptions): Promise<Module>;
+        parseFileSync(path: string, options: ParseOptions & { recover: true }): RecoveredModule;
         parseFileSync(path: string, options?: ParseOptions): Module;
         /**
          * Note: this method should be invoked on the compiler instance used
@@ -19,27 +23,152 @@ declare module "@swc/core" {
          */
         prinSynct(m: Module, options?: Options): Output;
 
+        /**
+         * Forgets config files read by this compiler.
+         *
+         * Resolved configs are cached per directory, and reloaded if a config
+         * file is modified. Watch tools may call this method if they know
+         * files affecting config resolution (like package.json) are changed.
+         */
+        clearConfigCache(): void;
+
+        /**
+         * Returns the config used to transform `filename`, without reading
+         * the file.
+         */
+        loadOptions(filename: string, options?: Options): LoadedOptions;
+
+        /**
+         * Returns a hex string which changes if the output for `filename`
+         * may change, except by the content of the file itself.
+         *
+         * The merged config, contents of config files, the version of swc and
+         * environment variables inlined by the optimizer are hashed. Paths
+         * are not, so keys are stable across processes and machines.
+         */
+        getCacheKey(filename: string, options?: Options): string;
+
         transform(src: string, options?: Options): Promise<Output>;
         transformSync(src: string, options?: Options): Output;
         transformFile(path: string, options?: Options): Promise<Output>;
         transformFileSync(path: string, options?: Options): Output;
     }
 
+    export function parse(src: string, options: ParseOptions & { recover: true }): Promise<RecoveredModule>;
     export function parse(src: string, options?: ParseOptions): Promise<Module>;
+    export function parseSync(src: string, options: ParseOptions & { recover: true }): RecoveredModule;
     export function parseSync(src: string, options?: ParseOptions): Module;
+    export function parseFile(path: string, options: ParseOptions & { recover: true }): Promise<RecoveredModule>;
     export function parseFile(path: string, options?: ParseOptions): Promise<Module>;
+    export function parseFileSync(path: string, options: ParseOptions & { recover: true }): RecoveredModule;
     export function parseFileSync(path: string, options?: ParseOptions): Module;
 
     export function print(m: Module, options?: Options): Promise<Output>;
     export function prinSynct(m: Module, options?: Options): Output;
 
+    export function clearConfigCache(): void;
+
+    export function loadOptions(filename: string, options?: Options): LoadedOptions;
+
+    export function getCacheKey(filename: string, options?: Options): string;
+
     export function transform(src: string, options?: Options): Promise<Output>;
     export function transformSync(src: string, options?: Options): Output;
     export function transformFile(path: string, options?: Options): Promise<Output>;
     export function transformFileSync(path: string, options?: Options): Output;
 
+    /**
+     * Options for `new Compiler()`.
+     */
+    export interface CompilerOptions {
+        /**
+         * Use ansi escape codes for code frames.
+         *
+         * "auto" uses colors only if stderr is a tty.
+         *
+         * Defaults to `"auto"`.
+         */
+        readonly color?: 'always' | 'never' | 'auto';
+
+        /**
+         * Include code frames in diagnostics, as `Diagnostic.codeFrame`.
+         *
+         * Defaults to `true`.
+         */
+        readonly codeFrame?: boolean;
+
+        /**
+         * Include code frames in error messages.
+         *
+         * Defaults to `true`.
+         */
+        readonly messageCodeFrame?: boolean;
+
+        /**
+         * Number of lines to print above and below the erroneous lines.
+         *
+         * Defaults to `2`.
+         */
+        readonly codeFrameLines?: number;
+
+        /**
+         * Shape of diagnostics, including `Output.warnings` and
+         * `SwcError.diagnostics`.
+         *
+         * If `"lsp"`, diagnostics are `LspDiagnostic`s of the language server
+         * protocol.
+         *
+         * Defaults to `"swc"`.
+         */
+        readonly diagnosticsFormat?: 'swc' | 'lsp';
+    }
+
     export type ParseOptions = ParserConfig & {
         readonly comments?: boolean;
+        /**
+         * If true, erroneous lines are skipped instead of rejecting, and
+         * errors are returned along with the module.
+         *
+         * Skipped lines which are not part of other statements are
+         * represented as `EmptyStatement`s, and spans of all skipped lines
+         * are returned as `skipped`. If errors can't be skipped this way, the
+         * rest of the file is skipped.
+         *
+         * The file is parsed again for each erroneous line, so recovery from
+         * many errors in a large file is slow. The number of attempts is
+         * limited by the size of the file.
+         *
+         * Defaults to `false`.
+         */
+        readonly recover?: boolean;
+    }
+
+    export interface LoadedOptions {
+        /**
+         * Config files and the programmatic options merged, with `env` and
+         * `overrides` applied.
+         */
+        readonly config: Config;
+        /**
+         * Absolute paths of config files merged into `config`, from the
+         * lowest precedence. A config file comes after the files it extends.
+         */
+        readonly files: string[];
+        /**
+         * Names of enabled passes in order, like `"compat::es2015"`, `"react"`
+         * or `"modules::common_js"`.
+         */
+        readonly passes: string[];
+    }
+
+    export interface RecoveredModule {
+        readonly module: Module;
+        /**
+         * Spans of skipped lines, sorted. An `EmptyStatement` with one of
+         * these spans is an error node.
+         */
+        readonly skipped: Span[];
+        readonly diagnostics: Diagnostic[];
     }
 
     /**
@@ -49,6 +178,11 @@ declare module "@swc/core" {
         /**
          * The working directory that all paths in the programmatic 
          * options will be resolved relative to.
+         *
+         * Relative `filename`, `root`, `configFile`, `swcrcRoots` and
+         * `traceFile` are resolved from it, and relative filenames are
+         * written into source maps as is, so they are relative to it.
+         * A relative `cwd` is resolved from `process.cwd()`.
          * 
          * Defaults to `process.cwd()`.
          */
@@ -76,7 +210,7 @@ declare module "@swc/core" {
          * This is used in two primary cases:
          * 
          * - The base directory when checking for the default "configFile" value
-         * - The default value for "swcrcRoots".
+         * - The boundary of the search for .swcrc files.
          * 
          * Defaults to `opts.cwd`
          */
@@ -89,27 +223,27 @@ declare module "@swc/core" {
          * 
          * "root" - Passes the "root" value through as unchanged.
          * "upward" - Walks upward from the "root" directory, looking for a directory
-         * containinga swc.config.js file, and throws an error if a swc.config.js
+         * containing a swc.config.json file, and throws an error if a swc.config.json
          * is not found.
          * "upward-optional" - Walk upward from the "root" directory, looking for
-         * a directory containing a swc.config.js file, and falls back to "root"
-         *  if a swc.config.js is not found.
+         * a directory containing a swc.config.json file, and falls back to "root"
+         *  if a swc.config.json is not found.
          *
          * 
          * "root" is the default mode because it avoids the risk that Swc 
-         * will accidentally load a swc.config.js that is entirely outside
+         * will accidentally load a swc.config.json that is entirely outside
          * of the current project folder. If you use "upward-optional",
          * be aware that it will walk up the directory structure all the
          * way to the filesystem root, and it is always possible that someone
-         * will have a forgotten swc.config.js in their home directory,
+         * will have a forgotten swc.config.json in their home directory,
          * which could cause unexpected errors in your builds.
          *
          * 
          * Users with monorepo project structures that run builds/tests on a
          * per-package basis may well want to use "upward" since monorepos
-         * often have a swc.config.js in the project root. Running Swc
+         * often have a swc.config.json in the project root. Running Swc
          * in a monorepo subdirectory without "upward", will cause Swc
-         * to skip loading any swc.config.js files in the project root,
+         * to skip loading any swc.config.json files in the project root,
          * which can lead to unexpected errors and compilation failure.
          */
         readonly rootMode?: 'root' | 'upward' | 'upward-optional';
@@ -137,7 +271,15 @@ declare module "@swc/core" {
          * a specific config file, it is recommended to stick with a
          * naming scheme that is independent of the "swcrc" name.
          * 
-         * Defaults to `path.resolve(opts.root, ".swcrc")`
+         * Defaults to `path.resolve(opts.root, "swc.config.json")`, if it exists
+         * and `opts.root` is set or `opts.rootMode` is "upward" or
+         * "upward-optional". A swc.config.json in `opts.cwd` is not loaded
+         * unless one of them is set.
+         * `true` always searches `opts.root`, and then `opts.cwd`.
+         * `false` disables loading of it.
+         *
+         * Use `configFile: false` with `swcrc: false` to apply only the
+         * programmatic options.
          */
         readonly configFile?: string | boolean;
 
@@ -148,31 +290,43 @@ declare module "@swc/core" {
          *
          * Note: .swcrc files are only loaded if the current "filename" is inside of
          *  a package that matches one of the "swcrcRoots" packages.
-         * 
+         *
+         * The search starts at the directory of "filename" and stops at the
+         * nearest directory containing a package.json, or at the root. In each
+         * directory, a `.swcrc` takes precedence over the "swc" key of
+         * package.json. The nearest package.json is a package boundary, so
+         * configs of parent packages are not used.
+         *
+         * `"type": "commonjs"` of the nearest package.json is used as the
+         * default for `module.type`. With `"type": "module"`, es modules are
+         * kept as is unless `module` is set.
+         *
+         * `false` disables all of them, including package.json.
          *
          * Defaults to true as long as the filename option has been specificed
          */
         readonly swcrc?: boolean;
 
         /**
-         * By default, Babel will only search for .babelrc files within the "root" package
-         *  because otherwise Babel cannot know if a given .babelrc is meant to be loaded,
-         *  or if it's "plugins" and "presets" have even been installed, since the file
-         *  being compiled could be inside node_modules, or have been symlinked into the project.
+         * Restricts the packages which may contribute a .swcrc file. The package
+         * of a file is the nearest directory containing a package.json.
          *
-         * 
-         * This option allows users to provide a list of other packages that should be
-         * considered "root" packages when considering whether to load .babelrc files.
+         * Paths and globs are resolved relative to `cwd`, and `*` does not
+         * match `/`, so `packages/*` does not match `packages/a/b`. Files in
+         * other packages use only the root config (`configFile`).
          *
-         * 
          * For example, a monorepo setup that wishes to allow individual packages
          * to have their own configs might want to do
          *
+         * ```js
+         * swcrcRoots: [".", "packages/*"]
+         * ```
          *
-         * 
-         * Defaults to `opts.root`
+         * `true` allows all packages, and `false` allows none.
+         *
+         * Defaults to allowing all packages.
          */
-        readonly swcrcRoots?: boolean | MatchPattern | MatchPattern[];
+        readonly swcrcRoots?: boolean | string | string[];
 
         /**
          * `true` will attempt to load an input sourcemap from the file itself, if it
@@ -211,20 +365,120 @@ declare module "@swc/core" {
          * The sourceRoot fields to set in the generated source map, if one is desired.
          */
         readonly sourceRoot?: string;
+
+        /**
+         * If true, `Output.timings` contains the time spent in each phase
+         * of the transform.
+         *
+         * Defaults to `false`.
+         */
+        readonly timings?: boolean;
+
+        /**
+         * Write the time spent in each phase to this file, in the chrome trace
+         * event format. The file can be loaded with `chrome://tracing`.
+         *
+         * The file is overwritten by each call.
+         */
+        readonly traceFile?: string;
     }
 
+    /**
+     * Describes the tool which invokes swc, like a bundler, so that one
+     * config can be used by different tools.
+     */
     export interface CallerOptions {
         readonly name: string,
+        /**
+         * The caller handles es modules, so `module` is not applied.
+         *
+         * Defaults to `false`.
+         */
+        readonly supportsStaticESM?: boolean,
+        /**
+         * The caller handles `import()`, so it is parsed and left as is.
+         *
+         * Defaults to `false`.
+         */
+        readonly supportsDynamicImport?: boolean,
         [key: string]: any
     }
 
     /**
      * .swcrc
+     *
+     * Configs are merged in the order `.swcrc`, `configFile`, their `env`
+     * section and `overrides`, and then the programmatic options with their
+     * own `env` section and `overrides`. A later config overrides an earlier
+     * one field by field, so the programmatic options always win:
+     *
+     * - Values like `jsc.target`, `jsc.externalHelpers` and `minify` are
+     *   replaced if set. An unset value keeps the earlier one.
+     * - Objects like `jsc.transform.react` are merged field by field.
+     *   `jsc.parser` and `module` are replaced as a whole.
+     * - Maps like `optimizer.globals.vars` and `constModules.globals` are
+     *   merged key by key.
+     * - Arrays like `optimizer.globals.envs` are replaced.
      */
     export interface Config {
+        /**
+         * Path or url of the JSON schema of this file, which is
+         * `@swc/core/lib/swcrc.schema.json`. Ignored by swc.
+         */
+        readonly $schema?: string;
+        /**
+         * Path of a config file to inherit from, relative to this config
+         * file. A package name can be used to load `.swcrc` of the package
+         * (or a file in it) from `node_modules`.
+         *
+         * Options of this config file override the extended one. When
+         * passed inline, it is resolved relative to `cwd`.
+         */
+        readonly extends?: string;
         readonly jsc?: JscConfig;
         readonly module?: ModuleConfig;
         readonly minify?: boolean;
+        /**
+         * Configs merged on top of this config if `envName` matches the key.
+         *
+         * ```json
+         * { "env": { "production": { "minify": true } } }
+         * ```
+         */
+        readonly env?: { readonly [envName: string]: Config };
+
+        /**
+         * If set, the config file is used only for files matching one of the
+         * patterns.
+         *
+         * Patterns are paths or globs, relative to the directory of the config
+         * file (or `cwd` for programmatic options). A path without glob
+         * characters matches all files in the directory.
+         */
+        readonly test?: string | string[];
+        /**
+         * Alias of `test`.
+         */
+        readonly include?: string | string[];
+        /**
+         * If set, the config file is not used for files matching one of the
+         * patterns.
+         */
+        readonly exclude?: string | string[];
+
+        /**
+         * Configs merged on top of this config if `test`, `include` and
+         * `exclude` of the entry match. Matching entries are merged in order.
+         *
+         * ```json
+         * {
+         *   "overrides": [
+         *     { "test": "src/legacy", "jsc": { "target": "es5" } }
+         *   ]
+         * }
+         * ```
+         */
+        readonly overrides?: Config[];
     }
 
     export interface JscConfig {
@@ -392,7 +646,8 @@ declare module "@swc/core" {
         readonly vars?: { [key: string]: string };
 
         /**
-         * Name of environment variables to inline.
+         * Name of environment variables to inline. Variables which are
+         * listed here but not set are left as is, with a warning.
          * 
          * Defaults to `["NODE_ENV", "SWC_ENV"]`
          */
@@ -493,6 +748,191 @@ declare module "@swc/core" {
          * Sourcemap (**not** base64 encoded)
          */
         map?: string;
+        /**
+         * Non-fatal diagnostics (below error level) reported while
+         * processing the file.
+         */
+        warnings: Diagnostic[];
+        /**
+         * Set if `timings` option is true.
+         */
+        timings?: Phase[];
+    }
+
+    /**
+     * A timed phase of a transform.
+     */
+    export interface Phase {
+        /**
+         * `resolveConfig`, `parse`, `pass:<name>` (e.g. `pass:hygiene`),
+         * `codegen` or `sourceMap`.
+         */
+        readonly name: string;
+        /**
+         * Milliseconds since the start of the transform.
+         */
+        readonly start: number;
+        /**
+         * Milliseconds.
+         */
+        readonly duration: number;
+    }
+
+    /**
+     * Error thrown (or used to reject a promise) by swc.
+     */
+    export interface SwcError extends Error {
+        readonly code: ErrorCode;
+        /**
+         * Path of the file being processed, if any.
+         */
+        readonly filename?: string;
+        /**
+         * Path of the file which caused the error, e.g. the `.swcrc` which
+         * failed to parse.
+         */
+        readonly path?: string;
+        /**
+         * Json path of the invalid value in a config file, e.g.
+         * `jsc.transform.optimizer.globals.vars`.
+         */
+        readonly jsonPath?: string;
+        /**
+         * Valid keys of the object containing an unknown key in a config file.
+         */
+        readonly validKeys?: string[];
+        /**
+         * The underlying io / json error.
+         */
+        readonly cause?: Error & {
+            /**
+             * Kind of the io error, e.g. `"NotFound"`.
+             */
+            readonly kind?: string;
+            readonly errno?: number;
+            readonly line?: number;
+            readonly column?: number;
+        };
+        /**
+         * Diagnostics reported while processing the file.
+         */
+        readonly diagnostics?: Diagnostic[];
+        /**
+         * Set if the error is from a config file extended by another config
+         * file. The first entry is the config file which is read, and the
+         * last one is the config file which caused the error.
+         */
+        readonly extendsChain?: string[];
+    }
+
+    export type ErrorCode = 'ERR_SWC_CONFIG_READ'
+        | 'ERR_SWC_CONFIG_PARSE'
+        | 'ERR_SWC_PARSE'
+        | 'ERR_SWC_READ_MODULE'
+        | 'ERR_SWC_EMIT'
+        | 'ERR_SWC_SOURCEMAP'
+        | 'ERR_SWC_SOURCEMAP_NOT_UTF8'
+        /**
+         * swc panicked. This is a bug of swc.
+         */
+        | 'ERR_SWC_PANIC'
+        | 'ERR_SWC_TRACE'
+        /**
+         * `rootMode` is "upward" and swc.config.json is not found.
+         */
+        | 'ERR_SWC_ROOT_CONFIG_NOT_FOUND'
+        /**
+         * `swcrcRoots` contains an invalid glob.
+         */
+        | 'ERR_SWC_SWCRC_ROOTS'
+        /**
+         * `test`, `include` or `exclude` contains an invalid glob.
+         */
+        | 'ERR_SWC_PATTERN'
+        | 'ERR_SWC_CONFIG_EXTENDS_NOT_FOUND'
+        | 'ERR_SWC_CONFIG_EXTENDS_CYCLE';
+
+    /**
+     * Values of `code` property of errors thrown by swc.
+     */
+    export const codes: { readonly [C in ErrorCode]: C };
+
+    export interface Diagnostic {
+        readonly severity: 'error' | 'warning' | 'note' | 'help';
+        readonly message: string;
+        /**
+         * Id of the diagnostic, or the code of the phase which emitted it:
+         * `"ERR_SWC_PARSE"` for the parser and `"ERR_SWC_TRANSFORM"` for
+         * others. Notes have no code.
+         */
+        readonly code?: string;
+        /**
+         * Name of the file this diagnostic belongs to.
+         */
+        readonly file?: string;
+        readonly span?: DiagnosticRange;
+        /**
+         * Source code around `span`. Rendered only if `codeFrame` is enabled.
+         */
+        readonly codeFrame?: string;
+        /**
+         * Sub-diagnostics attached to this diagnostic.
+         */
+        readonly notes?: Diagnostic[];
+    }
+
+    /**
+     * `Diagnostic` of the language server protocol, used if
+     * `diagnosticsFormat` is `"lsp"`.
+     */
+    export interface LspDiagnostic {
+        readonly range: LspRange;
+        /**
+         * 1: error, 2: warning, 3: information, 4: hint
+         */
+        readonly severity: 1 | 2 | 3 | 4;
+        /**
+         * Same as `Diagnostic.code`.
+         */
+        readonly code: string;
+        readonly source: 'swc';
+        readonly message: string;
+        readonly relatedInformation?: {
+            readonly location: { readonly uri: string, readonly range: LspRange };
+            readonly message: string;
+        }[];
+    }
+
+    export interface LspRange {
+        readonly start: LspPosition;
+        readonly end: LspPosition;
+    }
+
+    export interface LspPosition {
+        /**
+         * 0-based line number.
+         */
+        readonly line: number;
+        /**
+         * 0-based column, in utf-16 code units.
+         */
+        readonly character: number;
+    }
+
+    export interface DiagnosticRange {
+        readonly start: DiagnosticPosition;
+        readonly end: DiagnosticPosition;
+    }
+
+    export interface DiagnosticPosition {
+        /**
+         * 1-based line number.
+         */
+        readonly line: number;
+        /**
+         * 0-based column number.
+         */
+        readonly column: number;
     }
 
 
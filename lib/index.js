@@ -4,6 +4,10 @@ const native = require('../native');
 const version = require('../package.json').version;
 
 class Compiler extends native.Compiler {
+    constructor(options) {
+        super(options || {});
+    }
+
     parse(src, options) {
         options = options || {};
         options.syntax = options.syntax || 'ecmascript';
@@ -83,9 +87,18 @@ class Compiler extends native.Compiler {
 
 const compiler = new Compiler();
 
+/**
+ * Values of `code` property of errors thrown by swc.
+ */
+const codes = Object.freeze(native.codes.reduce((codes, code) => {
+    codes[code] = code;
+    return codes;
+}, {}));
+
 module.exports = {
     Compiler,
     version,
+    codes,
 
     parse: function parse() {
         return compiler.parse.apply(compiler, arguments)
@@ -127,6 +140,18 @@ module.exports = {
         return compiler.transformFileSync.apply(compiler, arguments);
     },
 
+    loadOptions: function loadOptions() {
+        return compiler.loadOptions.apply(compiler, arguments);
+    },
+
+    getCacheKey: function getCacheKey() {
+        return compiler.getCacheKey.apply(compiler, arguments);
+    },
+
+    clearConfigCache: function clearConfigCache() {
+        return compiler.clearConfigCache();
+    },
+
     DEFAULT_EXTENSIONS: Object.freeze([
         ".js",
         ".jsx",
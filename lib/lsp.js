@@ -0,0 +1,171 @@
+#!/usr/bin/env node
+/**
+ * A minimal language server which publishes diagnostics of swc.
+ *
+ * Files are transformed using `.swcrc` files of the project, just like
+ * `transformFileSync()`, so syntax errors and invalid configs are reported.
+ *
+ * Usage: `swc-lsp` (communicates over stdio)
+ */
+const { Compiler } = require('./index');
+const path = require('path');
+
+const compiler = new Compiler({
+    color: 'never',
+    codeFrame: false,
+    messageCodeFrame: false,
+    diagnosticsFormat: 'lsp',
+});
+
+/**
+ * Returns diagnostics of `text`, in the format of the language server protocol.
+ */
+function diagnose(filename, text) {
+    let warnings;
+    try {
+        warnings = compiler.transformSync(text, { filename }).warnings;
+    } catch (err) {
+        if (err.diagnostics) {
+            return err.diagnostics;
+        }
+
+        // Errors which are not bound to a location, like an invalid .swcrc
+        return [{
+            range: { start: { line: 0, character: 0 }, end: { line: 0, character: 0 } },
+            severity: 1,
+            code: err.code,
+            source: 'swc',
+            message: err.message,
+        }];
+    }
+
+    return warnings;
+}
+
+/**
+ * Converts a `file:` uri to a path. Other uris are returned as is.
+ *
+ * `url.fileURLToPath()` is not used, as it requires node 10.12.
+ */
+function uriToPath(uri, platform = process.platform) {
+    const match = /^file:\/\/([^/]*)(\/.*)?$/i.exec(uri);
+    if (!match) {
+        return uri;
+    }
+
+    const host = match[1];
+    let file;
+    try {
+        file = decodeURIComponent(match[2] || '/');
+    } catch (err) {
+        return uri;
+    }
+
+    if (platform !== 'win32') {
+        return file;
+    }
+    if (host && host !== 'localhost') {
+        // UNC path
+        file = `//${host}${file}`;
+    } else if (/^\/[a-z]:/i.test(file)) {
+        file = file.slice(1);
+    }
+    return file.replace(/\//g, '\\');
+}
+
+/**
+ * Handles messages from `input` and writes responses to `output`.
+ */
+function createServer(input, output) {
+    let buf = Buffer.alloc(0);
+    let shutdown = false;
+
+    function send(message) {
+        const body = Buffer.from(JSON.stringify(Object.assign({ jsonrpc: '2.0' }, message)), 'utf8');
+        output.write(`Content-Length: ${body.length}\r\n\r\n`);
+        output.write(body);
+    }
+
+    function publish(uri, text) {
+        const filename = uriToPath(uri);
+        send({
+            method: 'textDocument/publishDiagnostics',
+            params: {
+                uri,
+                diagnostics: path.isAbsolute(filename) ? diagnose(filename, text) : [],
+            },
+        });
+    }
+
+    function handle(message) {
+        const { id, method, params } = message;
+
+        switch (method) {
+            case 'initialize':
+                return send({
+                    id,
+                    result: {
+                        capabilities: {
+                            // Full
+                            textDocumentSync: 1,
+                        },
+                        serverInfo: { name: 'swc' },
+                    },
+                });
+            case 'shutdown':
+                shutdown = true;
+                return send({ id, result: null });
+            case 'exit':
+                return process.exit(shutdown ? 0 : 1);
+            case 'textDocument/didOpen':
+                return publish(params.textDocument.uri, params.textDocument.text);
+            case 'textDocument/didChange': {
+                const changes = params.contentChanges;
+                return publish(params.textDocument.uri, changes[changes.length - 1].text);
+            }
+            case 'textDocument/didClose':
+                return send({
+                    method: 'textDocument/publishDiagnostics',
+                    params: { uri: params.textDocument.uri, diagnostics: [] },
+                });
+            default:
+                // Notifications are ignored, but requests must be answered.
+                if (id !== undefined) {
+                    send({ id, error: { code: -32601, message: `unknown method: ${method}` } });
+                }
+        }
+    }
+
+    input.on('data', (chunk) => {
+        buf = Buffer.concat([buf, chunk]);
+
+        while (true) {
+            const headerEnd = buf.indexOf('\r\n\r\n');
+            if (headerEnd === -1) return;
+
+            const header = buf.slice(0, headerEnd).toString('ascii');
+            const match = /Content-Length: *(\d+)/i.exec(header);
+            const length = match ? parseInt(match[1], 10) : 0;
+            const start = headerEnd + 4;
+            if (buf.length < start + length) return;
+
+            const body = buf.slice(start, start + length).toString('utf8');
+            buf = buf.slice(start + length);
+
+            let message;
+            try {
+                message = JSON.parse(body);
+            } catch (err) {
+                send({ id: null, error: { code: -32700, message: `parse error: ${err.message}` } });
+                continue;
+            }
+            handle(message);
+        }
+    });
+}
+
+module.exports = { createServer, diagnose, uriToPath };
+
+if (require.main === module) {
+    createServer(process.stdin, process.stdout);
+}
@@ -1,7 +1,44 @@
 extern crate neon_build;
+extern crate serde_json;
+extern crate toml;
+
+use std::{env, fs, path::Path};
 
 fn main() {
     neon_build::setup(); // must be called in build.rs
 
     // add project-specific build logic here...
+
+    // Version of swc, used for cache keys. It's pinned in `Cargo.toml`, as
+    // `Cargo.lock` is not tracked.
+    let manifest = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join("Cargo.toml");
+    println!("cargo:rerun-if-changed={}", manifest.display());
+    let manifest = fs::read_to_string(&manifest).unwrap();
+    let rev = swc_rev(&manifest)
+        .expect("`swc` in Cargo.toml must be pinned with `rev`, as it's used for cache keys");
+    println!("cargo:rustc-env=SWC_VERSION={}", rev);
+
+    // Version of the npm package, as the version of this crate is not bumped
+    // on releases.
+    let pkg = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join("../package.json");
+    println!("cargo:rerun-if-changed={}", pkg.display());
+    let pkg: serde_json::Value = serde_json::from_str(&fs::read_to_string(&pkg).unwrap())
+        .expect("failed to parse package.json");
+    let version = pkg["version"]
+        .as_str()
+        .expect("package.json must have `version`");
+    println!("cargo:rustc-env=NPM_PACKAGE_VERSION={}", version);
+}
+
+/// Returns `rev` of the `swc` dependency in `Cargo.toml`.
+fn swc_rev(manifest: &str) -> Option<String> {
+    let manifest: toml::Value = manifest.parse().expect("failed to parse Cargo.toml");
+
+    manifest
+        .get("dependencies")?
+        .get("swc")?
+        .get("rev")?
+        .as_str()
+        .filter(|rev| !rev.is_empty())
+        .map(String::from)
 }
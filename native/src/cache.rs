@@ -0,0 +1,144 @@
+use crate::{
+    config::{Config, PackageType},
+    error::Error,
+};
+use hashbrown::HashMap;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::SystemTime,
+};
+use swc::{atoms::JsWord, ecmascript::ast::Expr};
+
+/// Caches resolved configs per directory, so that config files are read once
+/// for all files in a directory.
+///
+/// Entries are invalidated if a config file which contributed to them, or
+/// a `.swcrc` which did not exist, is modified.
+#[derive(Default)]
+pub(crate) struct ConfigCache {
+    entries: Mutex<HashMap<CacheKey, Entry>>,
+}
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub(crate) struct CacheKey {
+    /// Directory of the file being compiled.
+    pub dir: Option<PathBuf>,
+    /// Options affecting config resolution, serialized as json.
+    pub options: String,
+}
+
+/// Config files found for a directory.
+#[derive(Clone, Default)]
+pub(crate) struct ConfigFiles {
+    /// `configFile` or `swc.config.json` of the root.
+    pub config_file: Option<Config>,
+    /// The nearest `.swcrc`, or `swc` of `package.json`.
+    pub swcrc: Option<Config>,
+    /// `type` of the nearest `package.json`.
+    pub package_type: Option<PackageType>,
+    /// Config file extended by the programmatic options.
+    pub extended: Option<Config>,
+}
+
+/// Result of a config resolution.
+pub(crate) struct Resolved {
+    pub files: ConfigFiles,
+    /// Files which affect the result, whether they exist or not.
+    pub deps: Vec<PathBuf>,
+}
+
+struct Entry {
+    files: ConfigFiles,
+    deps: Vec<(PathBuf, Option<SystemTime>)>,
+}
+
+impl ConfigCache {
+    /// Returns the cached config files for `key`, calling `op` if there's no
+    /// valid entry.
+    pub fn get_or_resolve<F>(&self, key: CacheKey, op: F) -> Result<ConfigFiles, Error>
+    where
+        F: FnOnce() -> Result<Resolved, Error>,
+    {
+        {
+            let entries = self.entries.lock().unwrap();
+            if let Some(entry) = entries.get(&key) {
+                let fresh = entry
+                    .deps
+                    .iter()
+                    .all(|&(ref path, mtime)| modified(path) == mtime);
+                if fresh {
+                    return Ok(entry.files.clone());
+                }
+            }
+        }
+
+        // The lock is not held while resolving, as it reads files.
+        let Resolved { files, deps } = op()?;
+        let deps = deps
+            .into_iter()
+            .map(|path| {
+                let mtime = modified(&path);
+                (path, mtime)
+            })
+            .collect();
+
+        self.entries.lock().unwrap().insert(
+            key,
+            Entry {
+                files: files.clone(),
+                deps,
+            },
+        );
+
+        Ok(files)
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
+/// Caches parsed values of inlined globals, as parsing them adds source files
+/// to the source map of the `Compiler`.
+#[derive(Default)]
+pub(crate) struct GlobalsCache {
+    entries: Mutex<HashMap<GlobalsKey, ParsedGlobals>>,
+}
+
+/// Sorted `vars` and values of `envs` of a `GlobalPassOption`.
+pub(crate) type GlobalsKey = (Vec<(String, String)>, Vec<(String, String)>);
+
+#[derive(Clone)]
+pub(crate) struct ParsedGlobals {
+    pub globals: HashMap<JsWord, Expr>,
+    pub envs: HashMap<JsWord, Expr>,
+}
+
+impl GlobalsCache {
+    /// Returns the cached globals for `key`, calling `op` if there's no entry.
+    pub fn get_or_build<F>(&self, key: GlobalsKey, op: F) -> ParsedGlobals
+    where
+        F: FnOnce(&GlobalsKey) -> ParsedGlobals,
+    {
+        if let Some(globals) = self.entries.lock().unwrap().get(&key) {
+            return globals.clone();
+        }
+
+        // The lock is not held while parsing, as it may panic.
+        let globals = op(&key);
+        self.entries.lock().unwrap().insert(key, globals.clone());
+
+        globals
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
+/// Returns `None` if `path` does not exist.
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
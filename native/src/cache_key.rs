@@ -0,0 +1,65 @@
+use crate::{
+    config::{Config, Options},
+    error::Error,
+};
+use sha2::{Digest, Sha256};
+use std::{env, fs};
+
+/// Version of swc, which is the git revision pinned in `Cargo.toml`.
+const SWC_VERSION: &str = env!("SWC_VERSION");
+
+/// Version of `@swc/core`, from `package.json`.
+const NPM_PACKAGE_VERSION: &str = env!("NPM_PACKAGE_VERSION");
+
+/// Computes a hash of everything affecting the output for a file, except its
+/// content.
+///
+/// `config` is the effective config. Only contents of config files are
+/// hashed, not their paths, so keys are stable across machines.
+pub(crate) fn cache_key(opts: &Options, config: &Config) -> Result<String, Error> {
+    let mut hasher = Sha256::new();
+
+    write(&mut hasher, NPM_PACKAGE_VERSION.as_bytes());
+    write(&mut hasher, SWC_VERSION.as_bytes());
+
+    // File patterns are absolute, and they are already applied.
+    let mut merged = config.clone();
+    merged.test = None;
+    merged.include = None;
+    merged.exclude = None;
+    // Keys of objects are sorted, as `serde_json::Map` is a `BTreeMap`.
+    let merged = serde_json::to_value(&merged).expect("failed to serialize config");
+    write(&mut hasher, merged.to_string().as_bytes());
+
+    let source_maps =
+        serde_json::to_string(&opts.source_maps).expect("failed to serialize source maps");
+    write(&mut hasher, source_maps.as_bytes());
+
+    let caller = serde_json::to_string(&opts.caller).expect("failed to serialize caller");
+    write(&mut hasher, caller.as_bytes());
+
+    for path in &config.files {
+        let content = fs::read(path).map_err(|err| Error::FailedToReadConfigFile {
+            path: path.clone(),
+            err,
+        })?;
+        write(&mut hasher, &content);
+    }
+
+    for name in config.inlined_envs() {
+        write(&mut hasher, name.as_bytes());
+        match env::var(&name) {
+            Ok(value) => write(&mut hasher, value.as_bytes()),
+            // Distinct from an empty value.
+            Err(..) => hasher.input(&[0xff]),
+        }
+    }
+
+    Ok(format!("{:x}", hasher.result()))
+}
+
+/// Writes `bytes` with its length, so that boundaries of values are hashed.
+fn write(hasher: &mut Sha256, bytes: &[u8]) {
+    hasher.input(&(bytes.len() as u64).to_le_bytes());
+    hasher.input(bytes);
+}
@@ -1,27 +1,113 @@
-use crate::Compiler;
+use crate::{
+    cache::ParsedGlobals,
+    diagnostics::{CodeFrameConfig, DiagnosticsFormat},
+    error::{DidYouMean, Error, ExtendsChain, Location},
+    schema, Compiler,
+};
+use globset::GlobBuilder;
 use hashbrown::{HashMap, HashSet};
-use serde::{Deserialize, Serialize};
-use std::{env, path::PathBuf, sync::Arc};
+use path_clean::clean;
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    collections::BTreeSet,
+    env, fs,
+    hash::Hash,
+    iter::Peekable,
+    mem,
+    path::{Path, PathBuf},
+    str::Chars,
+    sync::Arc,
+};
 use swc::{
     atoms::JsWord,
-    common::{FileName, SourceMap},
+    common::{FileName, Fold, FoldWith, SourceMap, SyntaxContext},
     ecmascript::{
-        ast::{Expr, Module, ModuleItem, Stmt},
+        ast::{Expr, ExprOrSuper, MemberExpr, Module, ModuleItem, Stmt},
         parser::{Parser, Session as ParseSess, SourceFileInput, Syntax},
         transforms::{
-            chain_at, compat, const_modules, fixer, helpers, hygiene, modules,
-            pass::{noop, Optional, Pass},
+            compat, const_modules, fixer, helpers, hygiene, modules,
+            pass::{noop, Pass},
             proposals::{class_properties, decorators, export},
-            react, resolver, simplifier, typescript, InlineGlobals,
+            react, resolver, simplifier, typescript,
+            util::HANDLER,
+            InlineGlobals,
         },
     },
 };
 
+/// Options for `new Compiler()`.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub(crate) struct CompilerOptions {
+    #[serde(default)]
+    pub color: ColorConfig,
+
+    /// Include code frames in diagnostics.
+    #[serde(default = "default_code_frame")]
+    pub code_frame: bool,
+
+    /// Include code frames in error messages.
+    #[serde(default = "default_code_frame")]
+    pub message_code_frame: bool,
+
+    /// Number of lines to print above and below the erroneous lines.
+    #[serde(default = "default_code_frame_lines")]
+    pub code_frame_lines: usize,
+
+    /// Shape of diagnostics passed to javascript.
+    #[serde(default)]
+    pub diagnostics_format: DiagnosticsFormat,
+}
+
+const fn default_code_frame() -> bool {
+    true
+}
+
+const fn default_code_frame_lines() -> usize {
+    2
+}
+
+impl CompilerOptions {
+    pub fn code_frame(&self) -> CodeFrameConfig {
+        CodeFrameConfig {
+            diagnostics: self.code_frame,
+            message: self.message_code_frame,
+            color: match self.color {
+                ColorConfig::Always => true,
+                ColorConfig::Never => false,
+                ColorConfig::Auto => atty::is(atty::Stream::Stderr),
+            },
+            context_lines: self.code_frame_lines,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub(crate) enum ColorConfig {
+    #[serde(rename = "always")]
+    Always,
+    #[serde(rename = "never")]
+    Never,
+    /// Use colors if stderr is a tty.
+    #[serde(rename = "auto")]
+    Auto,
+}
+
+impl Default for ColorConfig {
+    fn default() -> Self {
+        ColorConfig::Auto
+    }
+}
+
 #[derive(Default, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct ParseOptions {
     #[serde(default)]
     pub comments: bool,
+    /// Skip erroneous lines instead of failing.
+    #[serde(default)]
+    pub recover: bool,
     #[serde(flatten)]
     pub syntax: Syntax,
 }
@@ -32,7 +118,8 @@ pub(crate) struct Options {
     #[serde(flatten, default)]
     pub config: Option<Config>,
 
-    #[serde(default = "default_cwd")]
+    /// Base of relative paths, like `filename`, `root` and `configFile`.
+    #[serde(default = "default_cwd", deserialize_with = "deserialize_cwd")]
     pub cwd: PathBuf,
 
     #[serde(default)]
@@ -54,7 +141,7 @@ pub(crate) struct Options {
     pub swcrc: bool,
 
     #[serde(default)]
-    pub swcrc_roots: Option<PathBuf>,
+    pub swcrc_roots: Option<SwcrcRoots>,
 
     #[serde(default = "default_env_name")]
     pub env_name: String,
@@ -70,6 +157,14 @@ pub(crate) struct Options {
 
     #[serde(default)]
     pub source_root: Option<String>,
+
+    /// Report how long each phase of the transform takes.
+    #[serde(default)]
+    pub timings: bool,
+
+    /// Write timings to this file in the chrome trace event format.
+    #[serde(default)]
+    pub trace_file: Option<PathBuf>,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -98,13 +193,87 @@ impl Default for InputSourceMap {
     }
 }
 
+/// Name of the project-wide config file, which is searched in the root
+/// directory.
+pub(crate) const ROOT_CONFIG_FILE: &str = "swc.config.json";
+
 impl Options {
-    pub fn build(&self, c: &Compiler, config: Option<Config>) -> BuiltConfig {
+    /// Resolves the project root using `root` and `root_mode`.
+    ///
+    /// Paths checked for the root config are added to `deps`.
+    pub fn root_dir(&self, deps: &mut Vec<PathBuf>) -> Result<PathBuf, Error> {
+        let root = match self.root {
+            Some(ref root) => self.cwd.join(root),
+            None => self.cwd.clone(),
+        };
+        let root = PathBuf::from(clean(&root.to_string_lossy()));
+
+        if self.root_mode == RootMode::Root {
+            return Ok(root);
+        }
+
+        let found = root
+            .ancestors()
+            .find(|dir| {
+                let path = dir.join(ROOT_CONFIG_FILE);
+                let found = path.is_file();
+                deps.push(path);
+                found
+            })
+            .map(Path::to_path_buf);
+
+        match found {
+            Some(dir) => Ok(dir),
+            None if self.root_mode == RootMode::UpwardOptional => Ok(root),
+            None => Err(Error::RootConfigNotFound {
+                root,
+                file: ROOT_CONFIG_FILE,
+            }),
+        }
+    }
+
+    /// Returns true if the project root is given by `root` or found by
+    /// `rootMode`, which enables loading of the root config file.
+    pub fn has_explicit_root(&self) -> bool {
+        self.root.is_some() || self.root_mode != RootMode::Root
+    }
+
+    /// Applies `env` and `overrides` to `config` from config files, and then
+    /// merges the programmatic config, with its own `env` and `overrides`
+    /// applied, so that the programmatic config takes precedence.
+    ///
+    /// `extended` is the config file extended by the programmatic config.
+    ///
+    /// `file` is the absolute path of the file being compiled.
+    pub fn effective_config(
+        &self,
+        config: Option<Config>,
+        extended: Option<Config>,
+        file: Option<&Path>,
+    ) -> Result<Config, Error> {
         let mut config = config.unwrap_or_else(|| Default::default());
+        config.apply_env_and_overrides(&self.env_name, file)?;
+
         if let Some(ref c) = self.config {
-            config.merge(c)
+            let mut c = c.clone();
+            c.resolve_patterns(&self.cwd);
+            c.extends = None;
+            if let Some(mut base) = extended {
+                base.merge(&c);
+                base.test = c.test;
+                base.include = c.include;
+                base.exclude = c.exclude;
+                c = base;
+            }
+            c.apply_env_and_overrides(&self.env_name, file)?;
+            config.merge(&c)
         }
 
+        Ok(config)
+    }
+
+    /// `config` should be the result of `effective_config`.
+    pub fn build(&self, c: &Compiler, config: Config) -> BuiltConfig {
         let JscConfig {
             transform,
             syntax,
@@ -112,69 +281,123 @@ impl Options {
             target,
         } = config.jsc;
 
-        let syntax = syntax.unwrap_or_default();
+        let caller = self.caller.clone().unwrap_or_default();
+
+        let mut syntax = syntax.unwrap_or_default();
+        // The caller handles `import()` by itself.
+        if caller.supports_dynamic_import {
+            match syntax {
+                Syntax::Es(ref mut c) => c.dynamic_import = true,
+                Syntax::Typescript(ref mut c) => c.dynamic_import = true,
+            }
+        }
         let transform = transform.unwrap_or_default();
+        let target = target.unwrap_or_default();
 
+        let enable_const_modules = transform.const_modules.is_some();
         let const_modules = {
-            let enabled = transform.const_modules.is_some();
             let config = transform.const_modules.unwrap_or_default();
 
             let globals = config.globals;
 
-            Optional::new(const_modules(globals), enabled)
+            const_modules(globals)
         };
 
         let optimizer = transform.optimizer;
         let enable_optimizer = optimizer.is_some();
-        let pass = if let Some(opts) =
-            optimizer.map(|o| o.globals.unwrap_or_else(|| Default::default()))
-        {
-            opts.build(c)
+        let globals = optimizer.and_then(|o| o.globals);
+        let unset_envs = globals
+            .as_ref()
+            .map(GlobalPassOption::unset_envs)
+            .unwrap_or_default();
+        // Without `globals`, no variable is inlined.
+        let pass = InlineGlobalsPass {
+            unset_envs: UnsetEnvs { names: unset_envs },
+            globals: globals
+                .unwrap_or_else(|| GlobalPassOption {
+                    vars: Default::default(),
+                    envs: Some(Default::default()),
+                })
+                .build(c),
+        };
+
+        // The caller handles es modules by itself.
+        let module = if caller.supports_static_esm {
+            None
         } else {
-            GlobalPassOption::default().build(c)
+            config.module
         };
 
-        let need_interop_analysis = match config.module {
+        let need_interop_analysis = match module {
             Some(ModuleConfig::CommonJs(ref c)) => !c.no_interop,
             Some(ModuleConfig::Amd(ref c)) => !c.config.no_interop,
             Some(ModuleConfig::Umd(ref c)) => !c.config.no_interop,
             None => false,
         };
 
-        let pass = chain_at!(
-            Module,
-            // handle jsx
-            Optional::new(react::react(c.cm.clone(), transform.react), syntax.jsx()),
-            Optional::new(typescript::strip(), syntax.typescript()),
-            resolver(),
-            const_modules,
-            pass,
-            Optional::new(decorators(), syntax.decorators()),
-            Optional::new(class_properties(), syntax.class_props()),
-            Optional::new(
-                export(),
-                syntax.export_default_from() || syntax.export_namespace_from()
-            ),
-            Optional::new(simplifier(), enable_optimizer),
-            Optional::new(compat::es2018(), target <= JscTarget::Es2018),
-            Optional::new(compat::es2017(), target <= JscTarget::Es2017),
-            Optional::new(compat::es2016(), target <= JscTarget::Es2016),
-            Optional::new(compat::es2015(), target <= JscTarget::Es2015),
-            Optional::new(compat::es3(), target <= JscTarget::Es3),
-            Optional::new(
-                modules::import_analysis::import_analyzer(),
-                need_interop_analysis
-            ),
-            helpers::InjectHelpers,
-            ModuleConfig::build(c.cm.clone(), config.module),
-            hygiene(),
-            fixer(),
+        // Passes are applied one by one, so that they can be timed.
+        let mut passes = Passes::default();
+        // handle jsx
+        passes.add(
+            "react",
+            syntax.jsx(),
+            react::react(c.cm.clone(), transform.react.build()),
         );
+        passes.add(
+            "typescript::strip",
+            syntax.typescript(),
+            typescript::strip(),
+        );
+        passes.add("resolver", true, resolver());
+        passes.add("const_modules", enable_const_modules, const_modules);
+        passes.add("inline_globals", true, pass);
+        passes.add("decorators", syntax.decorators(), decorators());
+        passes.add("class_properties", syntax.class_props(), class_properties());
+        passes.add(
+            "export",
+            syntax.export_default_from() || syntax.export_namespace_from(),
+            export(),
+        );
+        passes.add("simplifier", enable_optimizer, simplifier());
+        passes.add(
+            "compat::es2018",
+            target <= JscTarget::Es2018,
+            compat::es2018(),
+        );
+        passes.add(
+            "compat::es2017",
+            target <= JscTarget::Es2017,
+            compat::es2017(),
+        );
+        passes.add(
+            "compat::es2016",
+            target <= JscTarget::Es2016,
+            compat::es2016(),
+        );
+        passes.add(
+            "compat::es2015",
+            target <= JscTarget::Es2015,
+            compat::es2015(),
+        );
+        passes.add("compat::es3", target <= JscTarget::Es3, compat::es3());
+        passes.add(
+            "import_analyzer",
+            need_interop_analysis,
+            modules::import_analysis::import_analyzer(),
+        );
+        passes.add("inject_helpers", true, helpers::InjectHelpers);
+        passes.add(
+            module.as_ref().map_or("modules", ModuleConfig::name),
+            module.is_some(),
+            ModuleConfig::build(c.cm.clone(), module),
+        );
+        passes.add("hygiene", true, hygiene());
+        passes.add("fixer", true, fixer());
 
         BuiltConfig {
             minify: config.minify.unwrap_or(false),
-            pass: box pass,
-            external_helpers,
+            passes,
+            external_helpers: external_helpers.unwrap_or(false),
             syntax,
             source_maps: self
                 .source_maps
@@ -208,6 +431,65 @@ const fn default_swcrc() -> bool {
     true
 }
 
+/// Packages which may contribute a `.swcrc` file.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum SwcrcRoots {
+    /// `true` allows all packages, and `false` allows none.
+    Bool(bool),
+    /// A path or a glob, relative to `cwd`.
+    One(String),
+    Many(Vec<String>),
+}
+
+impl SwcrcRoots {
+    /// Returns true if `.swcrc` files in the package at `pkg_dir` can be
+    /// loaded. Patterns are relative to `cwd`, and `*` does not match `/`.
+    pub fn allows(&self, cwd: &Path, pkg_dir: &Path) -> Result<bool, Error> {
+        let patterns = match *self {
+            SwcrcRoots::Bool(v) => return Ok(v),
+            SwcrcRoots::One(ref s) => ::std::slice::from_ref(s),
+            SwcrcRoots::Many(ref v) => &**v,
+        };
+
+        for pattern in patterns {
+            let path = clean(&cwd.join(pattern).to_string_lossy());
+            let glob = GlobBuilder::new(&path)
+                .literal_separator(true)
+                .build()
+                .map_err(|err| Error::InvalidSwcrcRoots {
+                    pattern: pattern.clone(),
+                    err,
+                })?;
+
+            if glob.compile_matcher().is_match(pkg_dir) {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+}
+
+/// Returns the nearest directory containing `package.json`, or the
+/// directory of the file if there's no such directory.
+///
+/// Paths checked for `package.json` are added to `deps`.
+pub(crate) fn package_dir<'a>(file: &'a Path, deps: &mut Vec<PathBuf>) -> Option<&'a Path> {
+    let dir = file.parent()?;
+
+    Some(
+        dir.ancestors()
+            .find(|dir| {
+                let path = dir.join("package.json");
+                let found = path.is_file();
+                deps.push(path);
+                found
+            })
+            .unwrap_or(dir),
+    )
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum ConfigFile {
@@ -221,20 +503,46 @@ impl Default for ConfigFile {
     }
 }
 
+/// Describes the tool which invokes swc, like a bundler.
 #[derive(Default, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct CallerOptions {
     pub name: String,
+
+    /// If true, es modules are left as is even if `module` is set.
+    #[serde(default, rename = "supportsStaticESM")]
+    pub supports_static_esm: bool,
+
+    /// If true, `import()` is parsed and left as is.
+    #[serde(default)]
+    pub supports_dynamic_import: bool,
 }
 
 fn default_cwd() -> PathBuf {
     ::std::env::current_dir().unwrap()
 }
 
+/// A relative `cwd` is resolved from the working directory of the process.
+fn deserialize_cwd<'de, D>(deserializer: D) -> Result<PathBuf, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let cwd = PathBuf::deserialize(deserializer)?;
+    Ok(PathBuf::from(clean(&absolute(&cwd).to_string_lossy())))
+}
+
 /// `.swcrc` file
-#[derive(Default, Clone, Serialize, Deserialize)]
+#[derive(Default, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields, rename_all = "camelCase")]
 pub(crate) struct Config {
+    /// Config files merged into this config, from the lowest precedence.
+    #[serde(skip)]
+    pub files: Vec<PathBuf>,
+
+    /// Allows referencing the schema, for editors.
+    #[serde(rename = "$schema", default, skip_serializing)]
+    pub schema: Option<String>,
+
     #[serde(default)]
     pub jsc: JscConfig,
 
@@ -243,34 +551,841 @@ pub(crate) struct Config {
 
     #[serde(default)]
     pub minify: Option<bool>,
+
+    /// Path of a config file, or a package name, to inherit from.
+    ///
+    /// Resolved when the config file is read.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extends: Option<String>,
+
+    /// Configs merged on top of this config if `envName` matches the key.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    #[schemars(with = "::std::collections::HashMap<String, Config>")]
+    pub env: HashMap<String, Config>,
+
+    /// If set, the config file is used only for matching files.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub test: Option<FileMatcher>,
+
+    /// Alias of `test`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub include: Option<FileMatcher>,
+
+    /// If set, the config file is not used for matching files.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exclude: Option<FileMatcher>,
+
+    /// Configs merged on top of this config if `test`, `include` and
+    /// `exclude` of the entry match. Matching entries are merged in order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub overrides: Vec<Config>,
 }
 
-/// One `BuiltConfig` per a directory with swcrc
+impl Config {
+    /// Makes file patterns relative to `base` absolute.
+    pub fn resolve_patterns(&mut self, base: &Path) {
+        for matcher in vec![&mut self.test, &mut self.include, &mut self.exclude] {
+            if let Some(ref mut matcher) = *matcher {
+                matcher.resolve(base);
+            }
+        }
+
+        for config in self.env.values_mut() {
+            config.resolve_patterns(base);
+        }
+        for config in &mut self.overrides {
+            config.resolve_patterns(base);
+        }
+    }
+
+    /// Names of environment variables inlined by the optimizer, sorted.
+    pub fn inlined_envs(&self) -> Vec<String> {
+        let globals = self
+            .jsc
+            .transform
+            .as_ref()
+            .and_then(|t| t.optimizer.as_ref())
+            .and_then(|o| o.globals.as_ref());
+
+        let mut envs: Vec<_> = match globals {
+            Some(&GlobalPassOption {
+                envs: Some(ref envs),
+                ..
+            }) => envs.iter().cloned().collect(),
+            Some(..) => default_envs().into_iter().collect(),
+            None => vec![],
+        };
+        envs.sort();
+        envs
+    }
+
+    /// Returns true if `test`, `include` and `exclude` allow `file`.
+    ///
+    /// If `file` is `None`, only configs without `test` and `include` match.
+    pub fn matches(&self, file: Option<&Path>) -> Result<bool, Error> {
+        let file = match file {
+            Some(file) => file,
+            None => return Ok(self.test.is_none() && self.include.is_none()),
+        };
+
+        for matcher in vec![&self.test, &self.include] {
+            if let Some(ref matcher) = *matcher {
+                if !matcher.matches(file)? {
+                    return Ok(false);
+                }
+            }
+        }
+        if let Some(ref exclude) = self.exclude {
+            if exclude.matches(file)? {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Applies `env` and then `overrides`, including ones nested in them.
+    fn apply_env_and_overrides(
+        &mut self,
+        env_name: &str,
+        file: Option<&Path>,
+    ) -> Result<(), Error> {
+        self.apply_env(env_name);
+        self.apply_overrides(env_name, file)
+    }
+
+    /// Merges the `env` section matching `env_name`, and drops the others.
+    ///
+    /// `env` of the section is applied first, and its `overrides` are left in
+    /// `overrides` of this config.
+    fn apply_env(&mut self, env_name: &str) {
+        let mut env = mem::replace(&mut self.env, Default::default());
+        if let Some(mut env) = env.remove(env_name) {
+            env.apply_env(env_name);
+            self.merge(&env);
+        }
+    }
+
+    /// Merges overrides matching `file` in order, each with its own `env` and
+    /// `overrides` applied.
+    fn apply_overrides(&mut self, env_name: &str, file: Option<&Path>) -> Result<(), Error> {
+        let overrides = mem::replace(&mut self.overrides, vec![]);
+
+        for mut config in overrides {
+            if config.matches(file)? {
+                config.apply_env_and_overrides(env_name, file)?;
+                self.merge(&config);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Paths or globs matched against file names.
+///
+/// A path without glob characters matches all files in the directory.
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub(crate) enum FileMatcher {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl FileMatcher {
+    fn patterns_mut(&mut self) -> &mut [String] {
+        match *self {
+            FileMatcher::One(ref mut s) => ::std::slice::from_mut(s),
+            FileMatcher::Many(ref mut v) => v,
+        }
+    }
+
+    fn patterns(&self) -> &[String] {
+        match *self {
+            FileMatcher::One(ref s) => ::std::slice::from_ref(s),
+            FileMatcher::Many(ref v) => v,
+        }
+    }
+
+    fn resolve(&mut self, base: &Path) {
+        for pattern in self.patterns_mut() {
+            *pattern = clean(&base.join(&*pattern).to_string_lossy());
+        }
+    }
+
+    fn matches(&self, file: &Path) -> Result<bool, Error> {
+        for pattern in self.patterns() {
+            let is_glob = pattern.contains(|c| match c {
+                '*' | '?' | '[' | '{' => true,
+                _ => false,
+            });
+
+            let matched = if is_glob {
+                GlobBuilder::new(pattern)
+                    .literal_separator(true)
+                    .build()
+                    .map_err(|err| Error::InvalidPattern {
+                        pattern: pattern.clone(),
+                        err,
+                    })?
+                    .compile_matcher()
+                    .is_match(file)
+            } else {
+                file.starts_with(pattern)
+            };
+
+            if matched {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+}
+
+/// Reads a config file, and config files extended by it.
+///
+/// Paths of extended config files are appended to `deps`.
+///
+/// On error, the absolute path of the file and the json path of the invalid
+/// value are reported. If the error is from an extended config file, the chain
+/// of `extends` is reported as well.
+pub(crate) fn read_config_file(path: &Path, deps: &mut Vec<PathBuf>) -> Result<Config, Error> {
+    let mut chain = vec![];
+    load_config_file(absolute(path), &mut chain, deps).map_err(|err| in_chain(chain, err))
+}
+
+fn absolute(path: &Path) -> PathBuf {
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        PathBuf::from(clean(
+            &env::current_dir().unwrap().join(path).to_string_lossy(),
+        ))
+    }
+}
+
+/// Attaches the chain of `extends` to an error from an extended config file.
+fn in_chain(chain: Vec<PathBuf>, err: Error) -> Error {
+    match err {
+        Error::ExtendsCycle { .. } => err,
+        _ if chain.len() > 1 => Error::InExtendedConfig {
+            chain: ExtendsChain(chain),
+            err: box err,
+        },
+        _ => err,
+    }
+}
+
+/// `chain` is the stack of files being loaded.
+fn load_config_file(
+    path: PathBuf,
+    chain: &mut Vec<PathBuf>,
+    deps: &mut Vec<PathBuf>,
+) -> Result<Config, Error> {
+    if chain.contains(&path) {
+        chain.push(path);
+        return Err(Error::ExtendsCycle {
+            chain: ExtendsChain(chain.clone()),
+        });
+    }
+    chain.push(path.clone());
+
+    let src = fs::read_to_string(&path).map_err(|err| Error::FailedToReadConfigFile {
+        path: path.clone(),
+        err,
+    })?;
+
+    let mut config = parse_config(&path, &src)?;
+    config.files = vec![path.clone()];
+    let config = link_config(config, &path, chain, deps)?;
+
+    chain.pop();
+    Ok(config)
+}
+
+/// Resolves relative patterns and `extends` of the config at `path`.
+fn link_config(
+    mut config: Config,
+    path: &Path,
+    chain: &mut Vec<PathBuf>,
+    deps: &mut Vec<PathBuf>,
+) -> Result<Config, Error> {
+    if let Some(dir) = path.parent() {
+        config.resolve_patterns(dir);
+    }
+
+    if let Some(spec) = config.extends.take() {
+        let dir = path.parent().unwrap_or_else(|| Path::new("/"));
+        let base_path = resolve_extends(path, dir, &spec)?;
+        deps.push(base_path.clone());
+
+        let mut base = load_config_file(base_path, chain, deps)?;
+        base.merge(&config);
+        // Patterns of the extending config decide which files it applies to.
+        base.test = config.test;
+        base.include = config.include;
+        base.exclude = config.exclude;
+        config = base;
+    }
+
+    Ok(config)
+}
+
+/// Reads the config file extended by `extends` of the programmatic options,
+/// which is resolved from `cwd`.
+pub(crate) fn read_extended_config(
+    cwd: &Path,
+    spec: &str,
+    deps: &mut Vec<PathBuf>,
+) -> Result<Config, Error> {
+    let path = resolve_extends(cwd, cwd, spec)?;
+    deps.push(path.clone());
+
+    read_config_file(&path, deps)
+}
+
+/// Fields of `package.json` used by swc.
+#[derive(Default, Deserialize)]
+pub(crate) struct PackageJson {
+    /// Same as `.swcrc`.
+    #[serde(default)]
+    pub swc: Option<Config>,
+
+    #[serde(default, rename = "type")]
+    pub module_type: Option<PackageType>,
+}
+
+/// `type` of `package.json`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub(crate) enum PackageType {
+    #[serde(rename = "module")]
+    Module,
+    #[serde(rename = "commonjs")]
+    CommonJs,
+}
+
+/// Reads `package.json`. Config files extended by the `swc` key are appended
+/// to `deps`.
+pub(crate) fn read_package_json(
+    path: &Path,
+    deps: &mut Vec<PathBuf>,
+) -> Result<PackageJson, Error> {
+    let path = absolute(path);
+
+    let src = fs::read_to_string(&path).map_err(|err| Error::FailedToReadConfigFile {
+        path: path.clone(),
+        err,
+    })?;
+    let mut pkg: PackageJson =
+        deserialize_file(&path, &mut serde_json::Deserializer::from_str(&src))?;
+
+    if let Some(mut config) = pkg.swc.take() {
+        config.files = vec![path.clone()];
+        let mut chain = vec![path.clone()];
+        let config =
+            link_config(config, &path, &mut chain, deps).map_err(|err| in_chain(chain, err))?;
+        pkg.swc = Some(config);
+    }
+
+    Ok(pkg)
+}
+
+/// Resolves `extends` of the config file at `from`.
+///
+/// Relative paths are resolved from `dir`, which is the directory of `from`.
+/// Other values are resolved from `node_modules` directories, and if a
+/// package directory is found, `.swcrc` of the package is used.
+fn resolve_extends(from: &Path, dir: &Path, spec: &str) -> Result<PathBuf, Error> {
+    if spec.starts_with("./") || spec.starts_with("../") || Path::new(spec).is_absolute() {
+        return Ok(PathBuf::from(clean(&dir.join(spec).to_string_lossy())));
+    }
+
+    for dir in dir.ancestors() {
+        let candidate = dir.join("node_modules").join(spec);
+        if candidate.is_file() {
+            return Ok(candidate);
+        }
+
+        let swcrc = candidate.join(".swcrc");
+        if swcrc.is_file() {
+            return Ok(swcrc);
+        }
+    }
+
+    Err(Error::ExtendsNotFound {
+        path: from.to_path_buf(),
+        spec: spec.to_string(),
+    })
+}
+
+/// Parses a config file.
+///
+/// JSON with comments and trailing commas is accepted. If the file is not a
+/// valid JSON even without them, it's parsed as JSON5.
+fn parse_config(path: &Path, src: &str) -> Result<Config, Error> {
+    let stripped = strip_jsonc(src);
+    let mut de = serde_json::Deserializer::from_str(&stripped);
+
+    let result = deserialize_file(path, &mut de).and_then(|config| {
+        de.end().map_err(|err| Error::FailedToParseConfigFile {
+            path: path.to_path_buf(),
+            json_path: String::from("."),
+            valid_keys: vec![],
+            did_you_mean: DidYouMean(None),
+            location: Location(None),
+            err,
+        })?;
+
+        Ok(config)
+    });
+
+    let is_syntax_error = match result {
+        Err(Error::FailedToParseConfigFile { ref err, .. }) => err.is_syntax() || err.is_eof(),
+        _ => false,
+    };
+    if !is_syntax_error {
+        return result;
+    }
+
+    // Report the error of JSON parser if it's not a JSON5 either, as it has the
+    // position of the error.
+    match json5::from_str::<serde_json::Value>(src) {
+        // Errors of `Value` have no position, so it's looked up by the path.
+        Ok(value) => deserialize_file(path, value).map_err(|mut err| {
+            if let Error::FailedToParseConfigFile {
+                ref json_path,
+                ref mut location,
+                ..
+            } = err
+            {
+                *location = Location(locate_json5(src, json_path));
+            }
+            err
+        }),
+        Err(..) => result,
+    }
+}
+
+/// Returns the 1-based line and column of `json_path` in a JSON5 document.
+///
+/// `json_path` is formatted like `serde_path_to_error`, e.g.
+/// `overrides[0].jsc`. For object members, the position of the key is
+/// returned, so that unknown keys can be located.
+fn locate_json5(src: &str, json_path: &str) -> Option<(usize, usize)> {
+    enum Frame {
+        /// The current key
+        Object(Option<String>),
+        /// The current index
+        Array(usize),
+    }
+
+    enum State {
+        Key,
+        Value,
+        AfterValue,
+    }
+
+    fn path(stack: &[Frame]) -> String {
+        let mut path = String::new();
+        for frame in stack {
+            match *frame {
+                Frame::Object(Some(ref key)) => {
+                    if !path.is_empty() {
+                        path.push('.');
+                    }
+                    path.push_str(key);
+                }
+                Frame::Object(None) => {}
+                Frame::Array(index) => path.push_str(&format!("[{}]", index)),
+            }
+        }
+        if path.is_empty() {
+            path.push('.');
+        }
+        path
+    }
+
+    let mut cursor = Cursor::new(src);
+    let mut stack = vec![];
+    let mut state = State::Value;
+
+    loop {
+        cursor.skip_trivia();
+        let pos = cursor.pos();
+        let c = cursor.peek()?;
+
+        match state {
+            State::Key if c == '}' => {
+                cursor.bump();
+                stack.pop();
+                state = State::AfterValue;
+            }
+            State::Key => {
+                let key = cursor.key()?;
+                if let Some(&mut Frame::Object(ref mut k)) = stack.last_mut() {
+                    *k = Some(key);
+                }
+                if path(&stack) == json_path {
+                    return Some(pos);
+                }
+
+                cursor.skip_trivia();
+                if cursor.bump()? != ':' {
+                    return None;
+                }
+                state = State::Value;
+            }
+            // An empty array, or a trailing comma
+            State::Value if c == ']' => {
+                cursor.bump();
+                stack.pop();
+                state = State::AfterValue;
+            }
+            State::Value => {
+                if path(&stack) == json_path {
+                    return Some(pos);
+                }
+
+                match c {
+                    '{' => {
+                        cursor.bump();
+                        stack.push(Frame::Object(None));
+                        state = State::Key;
+                    }
+                    '[' => {
+                        cursor.bump();
+                        stack.push(Frame::Array(0));
+                    }
+                    '"' | '\'' => {
+                        cursor.string()?;
+                        state = State::AfterValue;
+                    }
+                    _ => {
+                        if cursor.word().is_empty() {
+                            return None;
+                        }
+                        state = State::AfterValue;
+                    }
+                }
+            }
+            State::AfterValue => {
+                let in_object = match stack.last() {
+                    Some(&Frame::Object(..)) => true,
+                    Some(&Frame::Array(..)) => false,
+                    None => return None,
+                };
+
+                match cursor.bump()? {
+                    ',' if in_object => state = State::Key,
+                    ',' => {
+                        if let Some(&mut Frame::Array(ref mut index)) = stack.last_mut() {
+                            *index += 1;
+                        }
+                        state = State::Value;
+                    }
+                    '}' if in_object => {
+                        stack.pop();
+                    }
+                    ']' if !in_object => {
+                        stack.pop();
+                    }
+                    _ => return None,
+                }
+            }
+        }
+    }
+}
+
+/// Iterates characters of a JSON5 document, tracking the 1-based line and
+/// column.
+struct Cursor<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(src: &'a str) -> Self {
+        Cursor {
+            chars: src.chars().peekable(),
+            line: 1,
+            column: 1,
+        }
+    }
+
+    fn pos(&self) -> (usize, usize) {
+        (self.line, self.column)
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().cloned()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    /// Skips whitespaces and comments.
+    fn skip_trivia(&mut self) {
+        loop {
+            match self.peek() {
+                Some(c) if c.is_whitespace() => {
+                    self.bump();
+                }
+                Some('/') => {
+                    let mut ahead = self.chars.clone();
+                    ahead.next();
+                    match ahead.next() {
+                        Some('/') => {
+                            while let Some(c) = self.bump() {
+                                if c == '\n' {
+                                    break;
+                                }
+                            }
+                        }
+                        Some('*') => {
+                            self.bump();
+                            self.bump();
+                            let mut prev = None;
+                            while let Some(c) = self.bump() {
+                                if prev == Some('*') && c == '/' {
+                                    break;
+                                }
+                                prev = Some(c);
+                            }
+                        }
+                        _ => return,
+                    }
+                }
+                _ => return,
+            }
+        }
+    }
+
+    /// Reads a quoted string. Escaped characters are kept as is.
+    fn string(&mut self) -> Option<String> {
+        let quote = self.bump()?;
+        let mut s = String::new();
+        loop {
+            match self.bump()? {
+                '\\' => s.push(self.bump()?),
+                c if c == quote => return Some(s),
+                c => s.push(c),
+            }
+        }
+    }
+
+    /// Reads an unquoted key, or a literal like `true` and `1.5`.
+    fn word(&mut self) -> String {
+        let mut s = String::new();
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() || ",:[]{}/".contains(c) {
+                break;
+            }
+            s.push(c);
+            self.bump();
+        }
+        s
+    }
+
+    fn key(&mut self) -> Option<String> {
+        match self.peek()? {
+            '"' | '\'' => self.string(),
+            _ => Some(self.word()).filter(|key| !key.is_empty()),
+        }
+    }
+}
+
+fn deserialize_file<'de, D, T>(path: &Path, de: D) -> Result<T, Error>
+where
+    D: Deserializer<'de, Error = serde_json::Error>,
+    T: Deserialize<'de>,
+{
+    serde_path_to_error::deserialize(de).map_err(|err| {
+        let mut json_path = err.path().to_string();
+        let err = err.into_inner();
+
+        let mut valid_keys = vec![];
+        let mut did_you_mean = None;
+        if let Some((key, keys)) = unknown_field(&err.to_string()) {
+            if !json_path.ends_with(&*key) {
+                if json_path != "." {
+                    json_path.push('.');
+                }
+                json_path.push_str(&key);
+            }
+
+            did_you_mean = keys
+                .iter()
+                .map(|k| (strsim::levenshtein(&key, k), k))
+                .filter(|&(distance, _)| distance <= 3)
+                .min_by_key(|&(distance, _)| distance)
+                .map(|(_, k)| k.clone());
+            valid_keys = keys;
+        }
+
+        Error::FailedToParseConfigFile {
+            path: path.to_path_buf(),
+            json_path,
+            valid_keys,
+            did_you_mean: DidYouMean(did_you_mean),
+            location: Location(None),
+            err,
+        }
+    })
+}
+
+/// Replaces comments and trailing commas with whitespaces, so that line and
+/// column numbers of errors are preserved.
+fn strip_jsonc(src: &str) -> String {
+    let mut buf = String::with_capacity(src.len());
+    let mut chars = src.chars().peekable();
+
+    // Strip comments.
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                buf.push(c);
+                while let Some(c) = chars.next() {
+                    buf.push(c);
+                    match c {
+                        '\\' => buf.extend(chars.next()),
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                buf.push(' ');
+                while let Some(&c) = chars.peek() {
+                    if c == '\n' {
+                        break;
+                    }
+                    blank(&mut buf, chars.next().unwrap());
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                buf.push(' ');
+                blank(&mut buf, chars.next().unwrap());
+                let mut prev = None;
+                while let Some(c) = chars.next() {
+                    blank(&mut buf, c);
+                    if prev == Some('*') && c == '/' {
+                        break;
+                    }
+                    prev = Some(c);
+                }
+            }
+            _ => buf.push(c),
+        }
+    }
+
+    // Strip trailing commas.
+    let mut bytes = buf.into_bytes();
+    let mut in_str = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if in_str => i += 1,
+            b'"' => in_str = !in_str,
+            b',' if !in_str => {
+                let next = bytes[i + 1..]
+                    .iter()
+                    .find(|b| !b.is_ascii_whitespace())
+                    .cloned();
+                if next == Some(b'}') || next == Some(b']') {
+                    bytes[i] = b' ';
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    // Only ascii characters are replaced.
+    String::from_utf8(bytes).unwrap()
+}
+
+/// Pushes whitespaces with the same length as `c`, keeping line breaks.
+fn blank(buf: &mut String, c: char) {
+    match c {
+        '\n' | '\r' => buf.push(c),
+        _ => {
+            for _ in 0..c.len_utf8() {
+                buf.push(' ');
+            }
+        }
+    }
+}
+
+/// Parses ``unknown field `foo`, expected one of `bar`, `baz` `` generated by
+/// serde, and returns `("foo", ["bar", "baz"])`.
+fn unknown_field(msg: &str) -> Option<(String, Vec<String>)> {
+    if !msg.starts_with("unknown field `") {
+        return None;
+    }
+
+    // Quoted parts are at odd indices.
+    let mut quoted = msg.split('`').skip(1).step_by(2).map(String::from);
+    let key = quoted.next()?;
+
+    Some((key, quoted.collect()))
+}
+
+/// Passes to apply, in order. Each pass has a name used for timings.
+#[derive(Default)]
+pub(crate) struct Passes(pub Vec<(&'static str, Box<dyn Pass>)>);
+
+impl Passes {
+    /// Appends `pass` if `enabled` is true.
+    fn add<P>(&mut self, name: &'static str, enabled: bool, pass: P)
+    where
+        P: Pass + 'static,
+    {
+        if enabled {
+            self.0.push((name, box pass));
+        }
+    }
+}
+
+/// Built for each file, from a config resolved once per directory.
+///
+/// Passes are not shared between files, as they keep per-module state, but
+/// values of inlined globals are parsed once and cached in the `Compiler`.
 pub(crate) struct BuiltConfig {
-    pub pass: Box<dyn Pass>,
+    pub passes: Passes,
     pub syntax: Syntax,
     pub minify: bool,
     pub external_helpers: bool,
     pub source_maps: bool,
 }
 
-#[derive(Default, Clone, Serialize, Deserialize)]
+#[derive(Default, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields, rename_all = "camelCase")]
 pub(crate) struct JscConfig {
     #[serde(rename = "parser", default)]
+    #[schemars(with = "Option<schema::Syntax>")]
     pub syntax: Option<Syntax>,
 
     #[serde(default)]
     pub transform: Option<TransformConfig>,
 
     #[serde(default)]
-    pub external_helpers: bool,
+    pub external_helpers: Option<bool>,
 
     #[serde(default)]
-    pub target: JscTarget,
+    pub target: Option<JscTarget>,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialOrd, Ord, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialOrd, Ord, PartialEq, Eq, JsonSchema)]
 pub(crate) enum JscTarget {
     #[serde(rename = "es3")]
     Es3,
@@ -294,19 +1409,28 @@ impl Default for JscTarget {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields, rename_all = "camelCase")]
 #[serde(tag = "type")]
 pub(crate) enum ModuleConfig {
     #[serde(rename = "commonjs")]
-    CommonJs(modules::common_js::Config),
+    CommonJs(#[schemars(with = "schema::CommonJsConfig")] modules::common_js::Config),
     #[serde(rename = "umd")]
-    Umd(modules::umd::Config),
+    Umd(#[schemars(with = "schema::UmdConfig")] modules::umd::Config),
     #[serde(rename = "amd")]
-    Amd(modules::amd::Config),
+    Amd(#[schemars(with = "schema::AmdConfig")] modules::amd::Config),
 }
 
 impl ModuleConfig {
+    /// Name of the pass.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            ModuleConfig::CommonJs(..) => "modules::common_js",
+            ModuleConfig::Umd(..) => "modules::umd",
+            ModuleConfig::Amd(..) => "modules::amd",
+        }
+    }
+
     pub fn build(cm: Arc<SourceMap>, config: Option<ModuleConfig>) -> Box<Pass> {
         match config {
             None => box noop(),
@@ -317,11 +1441,11 @@ impl ModuleConfig {
     }
 }
 
-#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields, rename_all = "camelCase")]
 pub(crate) struct TransformConfig {
     #[serde(default)]
-    pub react: react::Options,
+    pub react: ReactConfig,
 
     #[serde(default)]
     pub const_modules: Option<ConstModulesConfig>,
@@ -330,27 +1454,82 @@ pub(crate) struct TransformConfig {
     pub optimizer: Option<OptimizerConfig>,
 }
 
-#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+/// Same as `react::Options`, but unset fields are distinguishable so that
+/// configs can be merged field by field.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub(crate) struct ReactConfig {
+    #[serde(default)]
+    pub pragma: Option<String>,
+    #[serde(default)]
+    pub pragma_frag: Option<String>,
+    #[serde(default)]
+    pub throw_if_namespace: Option<bool>,
+    #[serde(default)]
+    pub development: Option<bool>,
+    #[serde(default)]
+    pub use_builtins: Option<bool>,
+}
+
+impl ReactConfig {
+    pub fn build(self) -> react::Options {
+        let mut opts = react::Options::default();
+        if let Some(pragma) = self.pragma {
+            opts.pragma = pragma;
+        }
+        if let Some(pragma_frag) = self.pragma_frag {
+            opts.pragma_frag = pragma_frag;
+        }
+        if let Some(throw_if_namespace) = self.throw_if_namespace {
+            opts.throw_if_namespace = throw_if_namespace;
+        }
+        if let Some(development) = self.development {
+            opts.development = development;
+        }
+        if let Some(use_builtins) = self.use_builtins {
+            opts.use_builtins = use_builtins;
+        }
+        opts
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields, rename_all = "camelCase")]
 pub(crate) struct ConstModulesConfig {
     #[serde(default)]
+    #[schemars(
+        with = "::std::collections::HashMap<String, ::std::collections::HashMap<String, String>>"
+    )]
     pub globals: HashMap<JsWord, HashMap<JsWord, String>>,
 }
 
-#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields, rename_all = "camelCase")]
 pub(crate) struct OptimizerConfig {
     #[serde(default)]
     pub globals: Option<GlobalPassOption>,
 }
 
-#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields, rename_all = "camelCase")]
 pub(crate) struct GlobalPassOption {
     #[serde(default)]
+    #[schemars(with = "::std::collections::HashMap<String, String>")]
     pub vars: HashMap<String, String>,
-    #[serde(default = "default_envs")]
-    pub envs: HashSet<String>,
+    /// Defaults to `default_envs()`.
+    #[serde(default, serialize_with = "serialize_sorted")]
+    #[schemars(with = "Option<::std::collections::BTreeSet<String>>")]
+    pub envs: Option<HashSet<String>>,
+}
+
+/// Serializes a set in a deterministic order.
+fn serialize_sorted<S>(set: &Option<HashSet<String>>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    set.as_ref()
+        .map(|set| set.iter().collect::<BTreeSet<_>>())
+        .serialize(serializer)
 }
 
 fn default_envs() -> HashSet<String> {
@@ -361,6 +1540,19 @@ fn default_envs() -> HashSet<String> {
 }
 
 impl GlobalPassOption {
+    /// Names of explicitly listed `envs` which are not set, and so are not
+    /// inlined.
+    ///
+    /// Default envs are not included, as they are usually not set.
+    fn unset_envs(&self) -> HashSet<String> {
+        self.envs
+            .iter()
+            .flatten()
+            .filter(|name| env::var_os(name).is_none())
+            .cloned()
+            .collect()
+    }
+
     pub fn build(self, c: &Compiler) -> InlineGlobals {
         fn mk_map(
             c: &Compiler,
@@ -410,11 +1602,91 @@ impl GlobalPassOption {
             m
         }
 
-        let envs = self.envs;
-        InlineGlobals {
-            globals: mk_map(c, self.vars.into_iter(), false),
-            envs: mk_map(c, env::vars().filter(|(k, _)| envs.contains(&*k)), true),
+        // Values are parsed into the long-lived source map, so they are
+        // parsed once for each set of values.
+        let envs = self.envs.unwrap_or_else(default_envs);
+        let mut envs: Vec<_> = env::vars().filter(|(k, _)| envs.contains(&*k)).collect();
+        envs.sort();
+        let mut vars: Vec<_> = self.vars.into_iter().collect();
+        vars.sort();
+
+        let ParsedGlobals { globals, envs } =
+            c.globals_cache
+                .get_or_build((vars, envs), |key| ParsedGlobals {
+                    globals: mk_map(c, key.0.iter().cloned(), false),
+                    envs: mk_map(c, key.1.iter().cloned(), true),
+                });
+        InlineGlobals { globals, envs }
+    }
+}
+
+/// `InlineGlobals`, which also warns about unset envs.
+struct InlineGlobalsPass {
+    unset_envs: UnsetEnvs,
+    globals: InlineGlobals,
+}
+
+impl Fold<Module> for InlineGlobalsPass {
+    fn fold(&mut self, module: Module) -> Module {
+        let module = if self.unset_envs.names.is_empty() {
+            module
+        } else {
+            module.fold_with(&mut self.unset_envs)
+        };
+        module.fold_with(&mut self.globals)
+    }
+}
+
+/// Warns about `process.env.FOO` which is left as is, as `FOO` is not set.
+struct UnsetEnvs {
+    names: HashSet<String>,
+}
+
+impl Fold<MemberExpr> for UnsetEnvs {
+    fn fold(&mut self, e: MemberExpr) -> MemberExpr {
+        let e = e.fold_children(self);
+
+        if let Some(name) = env_name(&e) {
+            if self.names.contains(name) {
+                HANDLER.with(|handler| {
+                    handler
+                        .struct_span_warn(
+                            e.span,
+                            &format!("`process.env.{}` is not inlined, as it's not set", name),
+                        )
+                        .emit()
+                });
+            }
+        }
+
+        e
+    }
+}
+
+/// Returns `FOO` of `process.env.FOO`, where `process` is the global.
+///
+/// Must be called after `resolver`, which marks local bindings.
+fn env_name(e: &MemberExpr) -> Option<&str> {
+    if e.computed {
+        return None;
+    }
+    let obj = match e.obj {
+        ExprOrSuper::Expr(box Expr::Member(ref obj)) if !obj.computed => obj,
+        _ => return None,
+    };
+
+    match (&obj.obj, &*obj.prop, &*e.prop) {
+        (
+            &ExprOrSuper::Expr(box Expr::Ident(ref process)),
+            &Expr::Ident(ref env),
+            &Expr::Ident(ref name),
+        ) if &*process.sym == "process"
+            && process.span.ctxt() == SyntaxContext::empty()
+            && &*env.sym == "env" =>
+        {
+            Some(&*name.sym)
         }
+        _ => None,
     }
 }
 
@@ -430,6 +1702,15 @@ fn default_env_name() -> String {
     }
 }
 
+/// Merges configs. `from` is the config with higher precedence, like the
+/// programmatic options merged into a config file.
+///
+/// - Scalars and enums, like `jsc.target` or `module`, are replaced if set in
+///   `from`.
+/// - Structs are merged field by field.
+/// - Maps, like `optimizer.globals.vars` and `constModules.globals`, are merged
+///   key by key.
+/// - Lists and sets, like `optimizer.globals.envs`, are replaced.
 pub(crate) trait Merge {
     /// Apply overrides from `from`
     fn merge(&mut self, from: &Self);
@@ -453,9 +1734,31 @@ where
 
 impl Merge for Config {
     fn merge(&mut self, from: &Self) {
+        self.files.extend(from.files.iter().cloned());
         self.jsc.merge(&from.jsc);
         self.module.merge(&from.module);
-        self.minify.merge(&from.minify)
+        self.minify.merge(&from.minify);
+        self.env.merge(&from.env);
+        // `test`, `include` and `exclude` apply to a config file itself, so
+        // they are not merged.
+        self.overrides.extend(from.overrides.iter().cloned());
+    }
+}
+
+impl<K, V> Merge for HashMap<K, V>
+where
+    K: Clone + Eq + Hash,
+    V: Clone + Merge,
+{
+    fn merge(&mut self, from: &Self) {
+        for (key, from) in from {
+            match self.get_mut(key) {
+                Some(v) => v.merge(from),
+                None => {
+                    self.insert(key.clone(), from.clone());
+                }
+            }
+        }
     }
 }
 
@@ -470,9 +1773,7 @@ impl Merge for JscConfig {
 
 impl Merge for JscTarget {
     fn merge(&mut self, from: &Self) {
-        if *self < *from {
-            *self = *from
-        }
+        *self = *from;
     }
 }
 
@@ -487,10 +1788,23 @@ impl Merge for Option<ModuleConfig> {
 
 impl Merge for bool {
     fn merge(&mut self, from: &Self) {
-        *self |= *from
+        *self = *from;
+    }
+}
+
+impl Merge for String {
+    fn merge(&mut self, from: &Self) {
+        self.clone_from(from);
+    }
+}
+
+impl Merge for HashSet<String> {
+    fn merge(&mut self, from: &Self) {
+        self.clone_from(from);
     }
 }
 
+/// `syntax` determines which fields are valid, so it's replaced as a whole.
 impl Merge for Syntax {
     fn merge(&mut self, from: &Self) {
         *self = *from;
@@ -513,18 +1827,23 @@ impl Merge for OptimizerConfig {
 
 impl Merge for GlobalPassOption {
     fn merge(&mut self, from: &Self) {
-        *self = from.clone();
+        self.vars.merge(&from.vars);
+        self.envs.merge(&from.envs);
     }
 }
 
-impl Merge for react::Options {
+impl Merge for ReactConfig {
     fn merge(&mut self, from: &Self) {
-        *self = from.clone();
+        self.pragma.merge(&from.pragma);
+        self.pragma_frag.merge(&from.pragma_frag);
+        self.throw_if_namespace.merge(&from.throw_if_namespace);
+        self.development.merge(&from.development);
+        self.use_builtins.merge(&from.use_builtins);
     }
 }
 
 impl Merge for ConstModulesConfig {
     fn merge(&mut self, from: &Self) {
-        *self = from.clone()
+        self.globals.merge(&from.globals);
     }
 }
@@ -0,0 +1,537 @@
+use serde::{
+    ser::{SerializeStruct, Serializer},
+    Deserialize, Serialize,
+};
+use std::{
+    cell::{Cell, RefCell},
+    fmt, mem,
+    path::Path,
+    sync::Arc,
+};
+use swc::common::{
+    errors::{Diagnostic as SwcDiagnostic, DiagnosticBuilder, DiagnosticId, Emitter, Level},
+    Loc, SourceMap, Span,
+};
+
+/// A diagnostic emitted by swc, in a form which can be passed to javascript.
+///
+/// The shape of the serialized value depends on `format`.
+#[derive(Debug, Clone)]
+pub(crate) struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    /// Id of the diagnostic, or the code of the phase which emitted it, e.g.
+    /// `ERR_SWC_PARSE`. Notes have no code.
+    pub code: Option<String>,
+    pub file: Option<String>,
+    pub span: Option<Range>,
+    /// Source code around `span`, rendered using [CodeFrameConfig].
+    pub code_frame: Option<String>,
+    /// Same as `code_frame`, but used for error messages.
+    pub message_code_frame: Option<String>,
+    pub notes: Vec<Diagnostic>,
+    pub format: DiagnosticsFormat,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub(crate) enum DiagnosticsFormat {
+    #[serde(rename = "swc")]
+    Swc,
+    /// `Diagnostic` of the language server protocol.
+    #[serde(rename = "lsp")]
+    Lsp,
+}
+
+impl Default for DiagnosticsFormat {
+    fn default() -> Self {
+        DiagnosticsFormat::Swc
+    }
+}
+
+/// Controls rendering of code frames.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct CodeFrameConfig {
+    /// Include code frames in diagnostics passed to javascript.
+    pub diagnostics: bool,
+    /// Include code frames in error messages.
+    pub message: bool,
+    /// Use ansi escape codes.
+    pub color: bool,
+    /// Number of lines to print above and below the erroneous lines.
+    pub context_lines: usize,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum Severity {
+    Help,
+    Note,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub(crate) struct Range {
+    pub start: Position,
+    pub end: Position,
+}
+
+/// `line` is 1-based and `column` is 0-based, like babel.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub(crate) struct Position {
+    pub line: usize,
+    pub column: usize,
+    /// Column in utf-16 code units, as required by the language server
+    /// protocol.
+    #[serde(skip)]
+    pub character: usize,
+}
+
+impl From<Level> for Severity {
+    fn from(level: Level) -> Self {
+        match level {
+            Level::Warning => Severity::Warning,
+            Level::Note | Level::FailureNote => Severity::Note,
+            Level::Help => Severity::Help,
+            _ => Severity::Error,
+        }
+    }
+}
+
+impl Diagnostic {
+    fn new(
+        cm: &SourceMap,
+        cfg: &CodeFrameConfig,
+        format: DiagnosticsFormat,
+        level: Level,
+        message: String,
+        span: Option<Span>,
+    ) -> Self {
+        let span = span.filter(|span| !span.is_dummy());
+        let (file, range) = match span {
+            Some(span) => {
+                let start = cm.lookup_char_pos(span.lo());
+                let end = cm.lookup_char_pos(span.hi());
+
+                let position = |loc: &Loc| Position {
+                    line: loc.line,
+                    column: loc.col.0,
+                    character: loc
+                        .file
+                        .get_line(loc.line - 1)
+                        .map(|line| line.chars().take(loc.col.0).map(char::len_utf16).sum())
+                        .unwrap_or(loc.col.0),
+                };
+
+                (
+                    Some(start.file.name.to_string()),
+                    Some(Range {
+                        start: position(&start),
+                        end: position(&end),
+                    }),
+                )
+            }
+            None => (None, None),
+        };
+        let code_frame = match span {
+            Some(span) if cfg.diagnostics || cfg.message => Some(render_code_frame(cm, cfg, span)),
+            _ => None,
+        };
+
+        Diagnostic {
+            severity: level.into(),
+            message,
+            code: None,
+            file,
+            span: range,
+            code_frame: code_frame.clone().filter(|_| cfg.diagnostics),
+            message_code_frame: code_frame.filter(|_| cfg.message),
+            notes: vec![],
+            format,
+        }
+    }
+
+    fn from_swc(
+        cm: &SourceMap,
+        cfg: &CodeFrameConfig,
+        format: DiagnosticsFormat,
+        d: &SwcDiagnostic,
+    ) -> Self {
+        let mut diagnostic =
+            Diagnostic::new(cm, cfg, format, d.level, d.message(), d.span.primary_span());
+        diagnostic.code = d.code.as_ref().map(|code| match *code {
+            DiagnosticId::Error(ref s) | DiagnosticId::Lint(ref s) => s.clone(),
+        });
+        diagnostic.notes = d
+            .children
+            .iter()
+            .map(|child| {
+                Diagnostic::new(
+                    cm,
+                    cfg,
+                    format,
+                    child.level,
+                    child.message(),
+                    child.span.primary_span(),
+                )
+            })
+            .collect();
+
+        diagnostic
+    }
+}
+
+impl Serialize for Diagnostic {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if self.format == DiagnosticsFormat::Lsp {
+            return LspDiagnostic::from(self).serialize(serializer);
+        }
+
+        let mut s = serializer.serialize_struct("Diagnostic", 7)?;
+        s.serialize_field("severity", &self.severity)?;
+        s.serialize_field("message", &self.message)?;
+        if let Some(ref code) = self.code {
+            s.serialize_field("code", code)?;
+        }
+        if let Some(ref file) = self.file {
+            s.serialize_field("file", file)?;
+        }
+        if let Some(ref span) = self.span {
+            s.serialize_field("span", span)?;
+        }
+        if let Some(ref code_frame) = self.code_frame {
+            s.serialize_field("codeFrame", code_frame)?;
+        }
+        if !self.notes.is_empty() {
+            s.serialize_field("notes", &self.notes)?;
+        }
+        s.end()
+    }
+}
+
+/// `Diagnostic` of the language server protocol.
+///
+/// Lines and characters are 0-based, and characters are counted in utf-16
+/// code units.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LspDiagnostic<'a> {
+    range: LspRange,
+    severity: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    code: Option<String>,
+    source: &'static str,
+    message: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    related_information: Vec<LspRelatedInformation<'a>>,
+}
+
+#[derive(Serialize)]
+struct LspRange {
+    start: LspPosition,
+    end: LspPosition,
+}
+
+#[derive(Serialize)]
+struct LspPosition {
+    line: usize,
+    character: usize,
+}
+
+#[derive(Serialize)]
+struct LspRelatedInformation<'a> {
+    location: LspLocation,
+    message: &'a str,
+}
+
+#[derive(Serialize)]
+struct LspLocation {
+    uri: String,
+    range: LspRange,
+}
+
+impl From<Option<Range>> for LspRange {
+    fn from(range: Option<Range>) -> Self {
+        let position = |p: Position| LspPosition {
+            line: p.line.saturating_sub(1),
+            character: p.character,
+        };
+
+        match range {
+            Some(range) => LspRange {
+                start: position(range.start),
+                end: position(range.end),
+            },
+            None => LspRange {
+                start: LspPosition {
+                    line: 0,
+                    character: 0,
+                },
+                end: LspPosition {
+                    line: 0,
+                    character: 0,
+                },
+            },
+        }
+    }
+}
+
+impl<'a> From<&'a Diagnostic> for LspDiagnostic<'a> {
+    fn from(d: &'a Diagnostic) -> Self {
+        let mut message = d.message.clone();
+        let mut related_information = vec![];
+
+        // Notes without a location can't be related information.
+        for note in &d.notes {
+            match (&note.file, note.span) {
+                (Some(file), Some(span)) => related_information.push(LspRelatedInformation {
+                    location: LspLocation {
+                        uri: uri(file),
+                        range: Some(span).into(),
+                    },
+                    message: &note.message,
+                }),
+                _ => {
+                    message.push('\n');
+                    message.push_str(&note.message);
+                }
+            }
+        }
+
+        LspDiagnostic {
+            range: d.span.into(),
+            severity: match d.severity {
+                Severity::Error => 1,
+                Severity::Warning => 2,
+                Severity::Note => 3,
+                Severity::Help => 4,
+            },
+            code: d.code.clone(),
+            source: "swc",
+            message,
+            related_information,
+        }
+    }
+}
+
+/// Converts an absolute path to a percent-encoded `file:///` uri. Other file
+/// names are returned as is.
+fn uri(file: &str) -> String {
+    if !Path::new(file).is_absolute() {
+        return file.to_string();
+    }
+
+    let path = file.replace('\\', "/");
+    let mut uri = String::from("file:");
+    if path.starts_with("//") {
+        // UNC path, whose server is the host of the uri
+    } else if path.starts_with('/') {
+        uri.push_str("//");
+    } else {
+        // `C:/foo` on windows
+        uri.push_str("///");
+    }
+
+    for b in path.bytes() {
+        match b {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' => uri.push(b as char),
+            b'-' | b'.' | b'_' | b'~' | b'/' | b':' | b'@' | b'!' | b'$' | b'&' | b'\'' | b'('
+            | b')' | b'*' | b'+' | b',' | b';' | b'=' => uri.push(b as char),
+            _ => uri.push_str(&format!("%{:02X}", b)),
+        }
+    }
+
+    uri
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Help => "help",
+            Severity::Note => "note",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(f, "{}: {}", severity, self.message)?;
+
+        if let Some(ref file) = self.file {
+            write!(f, "\n --> {}", file)?;
+            if let Some(ref span) = self.span {
+                write!(f, ":{}:{}", span.start.line, span.start.column + 1)?;
+            }
+        }
+
+        if let Some(ref code_frame) = self.message_code_frame {
+            write!(f, "\n{}", code_frame)?;
+        }
+
+        for note in &self.notes {
+            write!(f, "\n = {}", note)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Renders lines around `span` in a babel-like format.
+///
+/// ```text
+///   1 | class Foo {
+/// > 2 |     bar(
+///     |        ^
+///   3 | }
+/// ```
+fn render_code_frame(cm: &SourceMap, cfg: &CodeFrameConfig, span: Span) -> String {
+    const MARKER: &str = "\x1b[31;1m";
+    const GUTTER: &str = "\x1b[90m";
+    const RESET: &str = "\x1b[0m";
+
+    let paint = |style: &str, s: &str| {
+        if cfg.color {
+            format!("{}{}{}", style, s, RESET)
+        } else {
+            s.to_string()
+        }
+    };
+
+    let lo = cm.lookup_char_pos(span.lo());
+    let hi = cm.lookup_char_pos(span.hi());
+    let lines = lo.file.src.lines().collect::<Vec<_>>();
+    if lines.is_empty() {
+        return String::new();
+    }
+
+    // 0-based
+    let first = lo.line.saturating_sub(1);
+    let last = hi.line.saturating_sub(1).max(first);
+    let start = first.saturating_sub(cfg.context_lines);
+    let end = (last + cfg.context_lines).min(lines.len() - 1);
+    let width = (end + 1).to_string().len();
+
+    let mut buf = String::new();
+    for idx in start..=end {
+        let line = lines.get(idx).cloned().unwrap_or("");
+        let erroneous = first <= idx && idx <= last;
+
+        if !buf.is_empty() {
+            buf.push('\n');
+        }
+        buf.push_str(&if erroneous {
+            paint(MARKER, ">")
+        } else {
+            String::from(" ")
+        });
+        buf.push_str(&paint(GUTTER, &format!(" {:>w$} |", idx + 1, w = width)));
+        if !line.is_empty() {
+            buf.push(' ');
+            buf.push_str(line);
+        }
+
+        if erroneous {
+            let from = if idx == first { lo.col.0 } else { 0 };
+            let to = if idx == last {
+                hi.col.0
+            } else {
+                line.chars().count()
+            };
+
+            // Keep tabs so the marker is aligned with the source.
+            let indent = line
+                .chars()
+                .take(from)
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect::<String>();
+            let marker = "^".repeat(to.saturating_sub(from).max(1));
+
+            buf.push('\n');
+            buf.push(' ');
+            buf.push_str(&paint(GUTTER, &format!(" {:>w$} |", "", w = width)));
+            buf.push(' ');
+            buf.push_str(&indent);
+            buf.push_str(&paint(MARKER, &marker));
+        }
+    }
+
+    buf
+}
+
+thread_local! {
+    static BUFFER: RefCell<Vec<Diagnostic>> = RefCell::new(vec![]);
+    /// Number of `collect` calls running on the current thread.
+    static DEPTH: Cell<usize> = Cell::new(0);
+}
+
+/// Stores diagnostics instead of printing them to a tty.
+///
+/// Diagnostics are stored per thread, and can be retrieved using
+/// [collect].
+pub(crate) struct CollectingEmitter {
+    cm: Arc<SourceMap>,
+    cfg: CodeFrameConfig,
+    format: DiagnosticsFormat,
+}
+
+impl CollectingEmitter {
+    pub fn new(cm: Arc<SourceMap>, cfg: CodeFrameConfig, format: DiagnosticsFormat) -> Self {
+        CollectingEmitter { cm, cfg, format }
+    }
+}
+
+impl Emitter for CollectingEmitter {
+    fn emit(&mut self, db: &DiagnosticBuilder) {
+        let d = Diagnostic::from_swc(&self.cm, &self.cfg, self.format, &**db);
+
+        BUFFER.with(|b| b.borrow_mut().push(d));
+    }
+}
+
+/// Invokes `op` and returns diagnostics emitted on the current thread while
+/// it runs.
+///
+/// `code` is set on diagnostics without an id, as swc emits most diagnostics
+/// without one. Diagnostics are also visible to the enclosing `collect` call,
+/// if any, which keeps the code set by the innermost call.
+///
+/// Diagnostics emitted outside of `collect` are dropped by the outermost call,
+/// so that they don't pile up or leak into results of later calls.
+pub(crate) fn collect<F, T>(code: &'static str, op: F) -> (T, Vec<Diagnostic>)
+where
+    F: FnOnce() -> T,
+{
+    /// Restores diagnostics of the enclosing call even if `op` panics.
+    struct Restore(Option<Vec<Diagnostic>>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            DEPTH.with(|d| d.set(d.get() - 1));
+            if let Some(prev) = self.0.take() {
+                BUFFER.with(|b| *b.borrow_mut() = prev);
+            }
+        }
+    }
+
+    let outermost = DEPTH.with(|d| {
+        let depth = d.get();
+        d.set(depth + 1);
+        depth == 0
+    });
+    let prev = BUFFER.with(|b| mem::replace(&mut *b.borrow_mut(), vec![]));
+    let mut restore = Restore(Some(if outermost { vec![] } else { prev }));
+
+    let ret = op();
+
+    let mut prev = restore.0.take().unwrap();
+    let mut diagnostics = BUFFER.with(|b| mem::replace(&mut *b.borrow_mut(), vec![]));
+    for d in &mut diagnostics {
+        d.code.get_or_insert_with(|| code.to_string());
+    }
+    if !outermost {
+        prev.extend(diagnostics.iter().cloned());
+    }
+    BUFFER.with(|b| *b.borrow_mut() = prev);
+
+    (ret, diagnostics)
+}
@@ -1,22 +1,88 @@
+use crate::diagnostics::Diagnostic;
 use failure::Fail;
-use lazy_static::lazy_static;
+use neon::prelude::*;
+use neon_serde;
 use serde_json;
 use sourcemap;
-use std::{io, string::FromUtf8Error};
+use std::{
+    fmt, io,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    string::FromUtf8Error,
+};
 
 #[derive(Debug, Fail)]
 pub(crate) enum Error {
-    #[fail(display = "failed to read config file: {}", err)]
-    FailedToReadConfigFile { err: io::Error },
+    #[fail(display = "failed to read config file {:?}: {}", path, err)]
+    FailedToReadConfigFile { path: PathBuf, err: io::Error },
 
-    #[fail(display = "failed to parse config file: {}", err)]
-    FailedToParseConfigFile { err: serde_json::error::Error },
+    #[fail(
+        display = "failed to parse config file {:?} at `{}`{}: {}{}",
+        path, json_path, location, err, did_you_mean
+    )]
+    FailedToParseConfigFile {
+        /// Absolute path of the config file.
+        path: PathBuf,
+        /// e.g. `jsc.transform.optimizer.globals.vars`
+        json_path: String,
+        /// Valid keys of the object containing an unknown key.
+        valid_keys: Vec<String>,
+        did_you_mean: DidYouMean,
+        /// Set if `err` has no position, as the file was parsed as JSON5.
+        location: Location,
+        err: serde_json::error::Error,
+    },
+
+    #[fail(
+        display = "failed to find {} in {:?} or its ancestors (rootMode: upward)",
+        file, root
+    )]
+    RootConfigNotFound {
+        /// Directory where the search started.
+        root: PathBuf,
+        file: &'static str,
+    },
+
+    #[fail(display = "invalid pattern in swcrcRoots `{}`: {}", pattern, err)]
+    InvalidSwcrcRoots {
+        pattern: String,
+        err: globset::Error,
+    },
+
+    #[fail(display = "invalid file pattern `{}`: {}", pattern, err)]
+    InvalidPattern {
+        pattern: String,
+        err: globset::Error,
+    },
+
+    #[fail(
+        display = "failed to resolve `extends: \"{}\"` of config file {:?}",
+        spec, path
+    )]
+    ExtendsNotFound {
+        /// Config file containing `extends`, or `cwd` for the programmatic
+        /// options.
+        path: PathBuf,
+        spec: String,
+    },
+
+    #[fail(display = "config files extend each other: {}", chain)]
+    ExtendsCycle { chain: ExtendsChain },
+
+    /// An error from a config file extended by another config file.
+    ///
+    /// `code`, `path` and `cause` are of the underlying error.
+    #[fail(display = "{}\n  in extends chain: {}", err, chain)]
+    InExtendedConfig {
+        chain: ExtendsChain,
+        err: Box<Error>,
+    },
 
     #[fail(display = "failed to parse module")]
-    FailedToParseModule {},
+    FailedToParseModule { diagnostics: Vec<Diagnostic> },
 
-    #[fail(display = "failed to read module: {}", err)]
-    FailedToReadModule { err: io::Error },
+    #[fail(display = "failed to read module {:?}: {}", path, err)]
+    FailedToReadModule { path: PathBuf, err: io::Error },
 
     #[fail(display = "failed to emit module: {}", err)]
     FailedToEmitModule { err: io::Error },
@@ -26,20 +92,270 @@ pub(crate) enum Error {
 
     #[fail(display = "sourcemap is not utf8: {}", err)]
     SourceMapNotUtf8 { err: FromUtf8Error },
+
+    #[fail(display = "failed to write trace file {:?}: {}", path, err)]
+    FailedToWriteTrace { path: PathBuf, err: io::Error },
+
+    #[fail(display = "swc panicked: {}", message)]
+    Panic { message: String },
     /* #[fail(display = "generated code is not utf8: {}", err)]
      * GeneratedCodeNotUtf8 { err: FromUtf8Error }, */
 }
 
-/// Returns true if `SWC_DEBUG` environment is set to `1` or `true`.
-pub(crate) fn debug() -> bool {
-    lazy_static! {
-        static ref DEBUG: bool = {
-            match ::std::env::var("SWC_DEBUG") {
-                Ok(ref v) if v == "1" || v.eq_ignore_ascii_case("true") => true,
-                _ => false,
+/// Suggestion for an unknown key.
+#[derive(Debug)]
+pub(crate) struct DidYouMean(pub Option<String>);
+
+impl fmt::Display for DidYouMean {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Some(ref key) => write!(f, " (did you mean `{}`?)", key),
+            None => Ok(()),
+        }
+    }
+}
+
+/// 1-based line and column in a config file.
+#[derive(Debug)]
+pub(crate) struct Location(pub Option<(usize, usize)>);
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Some((line, column)) => write!(f, " (line {}, column {})", line, column),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Config files from the one which is being read to the one which caused an
+/// error.
+#[derive(Debug)]
+pub(crate) struct ExtendsChain(pub Vec<PathBuf>);
+
+impl fmt::Display for ExtendsChain {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, path) in self.0.iter().enumerate() {
+            if i != 0 {
+                write!(f, " -> ")?;
+            }
+            write!(f, "{}", path.display())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// All values [Error::code] can return.
+///
+/// Exported to javascript as `codes`. Codes are part of the public api, so
+/// they should not be changed or removed.
+pub(crate) const CODES: &[&str] = &[
+    "ERR_SWC_CONFIG_READ",
+    "ERR_SWC_CONFIG_PARSE",
+    "ERR_SWC_PARSE",
+    "ERR_SWC_READ_MODULE",
+    "ERR_SWC_EMIT",
+    "ERR_SWC_SOURCEMAP",
+    "ERR_SWC_SOURCEMAP_NOT_UTF8",
+    "ERR_SWC_PANIC",
+    "ERR_SWC_TRACE",
+    "ERR_SWC_ROOT_CONFIG_NOT_FOUND",
+    "ERR_SWC_SWCRC_ROOTS",
+    "ERR_SWC_PATTERN",
+    "ERR_SWC_CONFIG_EXTENDS_NOT_FOUND",
+    "ERR_SWC_CONFIG_EXTENDS_CYCLE",
+];
+
+impl Error {
+    /// Stable code of the error, exposed to javascript as `code`.
+    pub fn code(&self) -> &'static str {
+        match *self {
+            Error::FailedToReadConfigFile { .. } => "ERR_SWC_CONFIG_READ",
+            Error::FailedToParseConfigFile { .. } => "ERR_SWC_CONFIG_PARSE",
+            Error::FailedToParseModule { .. } => "ERR_SWC_PARSE",
+            Error::FailedToReadModule { .. } => "ERR_SWC_READ_MODULE",
+            Error::FailedToEmitModule { .. } => "ERR_SWC_EMIT",
+            Error::FailedToWriteSourceMap { .. } => "ERR_SWC_SOURCEMAP",
+            Error::SourceMapNotUtf8 { .. } => "ERR_SWC_SOURCEMAP_NOT_UTF8",
+            Error::Panic { .. } => "ERR_SWC_PANIC",
+            Error::FailedToWriteTrace { .. } => "ERR_SWC_TRACE",
+            Error::RootConfigNotFound { .. } => "ERR_SWC_ROOT_CONFIG_NOT_FOUND",
+            Error::InvalidSwcrcRoots { .. } => "ERR_SWC_SWCRC_ROOTS",
+            Error::InvalidPattern { .. } => "ERR_SWC_PATTERN",
+            Error::ExtendsNotFound { .. } => "ERR_SWC_CONFIG_EXTENDS_NOT_FOUND",
+            Error::ExtendsCycle { .. } => "ERR_SWC_CONFIG_EXTENDS_CYCLE",
+            Error::InExtendedConfig { ref err, .. } => err.code(),
+        }
+    }
+
+    /// Path of the file which caused the error.
+    pub fn path(&self) -> Option<&Path> {
+        match *self {
+            Error::FailedToReadConfigFile { ref path, .. }
+            | Error::FailedToParseConfigFile { ref path, .. }
+            | Error::FailedToReadModule { ref path, .. }
+            | Error::FailedToWriteTrace { ref path, .. }
+            | Error::ExtendsNotFound { ref path, .. } => Some(path),
+            Error::InExtendedConfig { ref err, .. } => err.path(),
+            _ => None,
+        }
+    }
+
+    /// Creates a javascript object for the underlying error.
+    fn cause<'a, C>(&self, cx: &mut C) -> NeonResult<Option<Handle<'a, JsError>>>
+    where
+        C: Context<'a>,
+    {
+        match *self {
+            Error::FailedToReadConfigFile { ref err, .. }
+            | Error::FailedToReadModule { ref err, .. }
+            | Error::FailedToEmitModule { ref err }
+            | Error::FailedToWriteTrace { ref err, .. } => {
+                let cause = JsError::error(cx, err.to_string())?;
+
+                let kind = cx.string(format!("{:?}", err.kind()));
+                cause.set(cx, "kind", kind)?;
+                if let Some(errno) = err.raw_os_error() {
+                    let errno = cx.number(errno);
+                    cause.set(cx, "errno", errno)?;
+                }
+
+                Ok(Some(cause))
+            }
+
+            Error::FailedToParseConfigFile {
+                ref err,
+                ref location,
+                ..
+            } => {
+                let cause = JsError::error(cx, err.to_string())?;
+
+                let (line, column) = location.0.unwrap_or_else(|| (err.line(), err.column()));
+                let line = cx.number(line as f64);
+                cause.set(cx, "line", line)?;
+                let column = cx.number(column as f64);
+                cause.set(cx, "column", column)?;
+
+                Ok(Some(cause))
+            }
+
+            Error::FailedToWriteSourceMap { ref err } => {
+                Ok(Some(JsError::error(cx, err.to_string())?))
+            }
+            Error::SourceMapNotUtf8 { ref err } => Ok(Some(JsError::error(cx, err.to_string())?)),
+            Error::InvalidSwcrcRoots { ref err, .. } | Error::InvalidPattern { ref err, .. } => {
+                Ok(Some(JsError::error(cx, err.to_string())?))
+            }
+
+            Error::InExtendedConfig { ref err, .. } => err.cause(cx),
+
+            Error::FailedToParseModule { .. }
+            | Error::RootConfigNotFound { .. }
+            | Error::ExtendsNotFound { .. }
+            | Error::ExtendsCycle { .. }
+            | Error::Panic { .. } => Ok(None),
+        }
+    }
+
+    /// Throws this error as a javascript `Error`.
+    ///
+    /// The thrown error has `code` and `filename` properties. `path` (the
+    /// file which caused the error) and `cause` (the underlying error) are
+    /// set if available. Diagnostics are available as `diagnostics` property.
+    /// Errors from extended config files have `extendsChain`.
+    pub fn throw<'a, C, T>(self, cx: &mut C, filename: Option<&Path>) -> NeonResult<T>
+    where
+        C: Context<'a>,
+    {
+        let mut msg = self.to_string();
+        if let Error::FailedToParseModule { ref diagnostics } = self {
+            for d in diagnostics {
+                msg.push_str("\n\n");
+                msg.push_str(&d.to_string());
+            }
+        }
+
+        let err = JsError::error(cx, msg)?;
+
+        let code = cx.string(self.code());
+        err.set(cx, "code", code)?;
+
+        if let Some(filename) = filename {
+            let filename = cx.string(filename.display().to_string());
+            err.set(cx, "filename", filename)?;
+        }
+
+        if let Some(path) = self.path() {
+            let path = cx.string(path.display().to_string());
+            err.set(cx, "path", path)?;
+        }
+
+        if let Some(cause) = self.cause(cx)? {
+            err.set(cx, "cause", cause)?;
+        }
+
+        let chain = match self {
+            Error::ExtendsCycle { ref chain } | Error::InExtendedConfig { ref chain, .. } => {
+                Some(chain)
             }
+            _ => None,
+        };
+        if let Some(chain) = chain {
+            let paths = JsArray::new(cx, chain.0.len() as u32);
+            for (i, path) in chain.0.iter().enumerate() {
+                let path = cx.string(path.display().to_string());
+                paths.set(cx, i as u32, path)?;
+            }
+            err.set(cx, "extendsChain", paths)?;
+        }
+
+        let inner = match self {
+            Error::InExtendedConfig { ref err, .. } => &**err,
+            ref err => err,
+        };
+        if let Error::FailedToParseConfigFile {
+            ref json_path,
+            ref valid_keys,
+            ..
+        } = *inner
+        {
+            let json_path = cx.string(json_path);
+            err.set(cx, "jsonPath", json_path)?;
+
+            let keys = JsArray::new(cx, valid_keys.len() as u32);
+            for (i, key) in valid_keys.iter().enumerate() {
+                let key = cx.string(key);
+                keys.set(cx, i as u32, key)?;
+            }
+            err.set(cx, "validKeys", keys)?;
+        }
+
+        if let Error::FailedToParseModule { ref diagnostics } = self {
+            let diagnostics = neon_serde::to_value(cx, diagnostics)?;
+            err.set(cx, "diagnostics", diagnostics)?;
+        }
+
+        cx.throw(err)
+    }
+}
+
+/// Invokes `op`, converting a panic into [Error::Panic].
+///
+/// Panics must not cross the ffi boundary, as it aborts the node process.
+pub(crate) fn catch_panic<F, T>(op: F) -> Result<T, Error>
+where
+    F: FnOnce() -> Result<T, Error>,
+{
+    panic::catch_unwind(AssertUnwindSafe(op)).unwrap_or_else(|payload| {
+        let message = if let Some(s) = payload.downcast_ref::<&str>() {
+            s.to_string()
+        } else if let Some(s) = payload.downcast_ref::<String>() {
+            s.clone()
+        } else {
+            String::from("unknown panic")
         };
-    };
 
-    *DEBUG
+        Err(Error::Panic { message })
+    })
 }
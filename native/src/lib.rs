@@ -3,42 +3,63 @@
 #![feature(never_type)]
 #![recursion_limit = "2048"]
 
+extern crate atty;
 extern crate fxhash;
+extern crate globset;
 #[macro_use]
 extern crate neon;
 extern crate failure;
 extern crate hashbrown;
+extern crate json5;
 extern crate lazy_static;
 extern crate neon_serde;
 extern crate path_clean;
+extern crate schemars;
 extern crate serde;
 extern crate serde_json;
+extern crate serde_path_to_error;
+extern crate sha2;
 extern crate sourcemap;
+extern crate strsim;
 extern crate swc;
 
+mod cache;
+mod cache_key;
 mod config;
+mod diagnostics;
 mod error;
+mod schema;
+mod timings;
 
 use crate::{
-    config::{BuiltConfig, Config, ConfigFile, Merge, Options, ParseOptions, RootMode},
-    error::Error,
+    cache::{CacheKey, ConfigCache, ConfigFiles, GlobalsCache, Resolved},
+    cache_key::cache_key,
+    config::{
+        package_dir, read_config_file, read_extended_config, read_package_json, BuiltConfig,
+        CompilerOptions, Config, ConfigFile, Merge, ModuleConfig, Options, PackageType,
+        ParseOptions, ROOT_CONFIG_FILE,
+    },
+    diagnostics::{CollectingEmitter, Diagnostic, Severity},
+    error::{catch_panic, Error},
+    timings::{Phase, Timings},
 };
 use neon::prelude::*;
 use path_clean::clean;
 use serde::Serialize;
 use sourcemap::SourceMapBuilder;
 use std::{
-    fs::File,
+    fs,
+    ops::Range,
     path::{Path, PathBuf},
     sync::Arc,
 };
 use swc::{
     common::{
-        self, comments::Comments, errors::Handler, FileName, FilePathMapping, FoldWith, Globals,
-        SourceFile, SourceMap, Spanned, GLOBALS,
+        comments::Comments, errors::Handler, BytePos, FileName, FilePathMapping, FoldWith, Globals,
+        SourceFile, SourceMap, Span, Spanned, SyntaxContext, GLOBALS,
     },
     ecmascript::{
-        ast::Module,
+        ast::{EmptyStmt, Module, ModuleItem, Stmt},
         codegen::{self, Emitter},
         parser::{Parser, Session as ParseSess, SourceFileInput, Syntax},
         transforms::{
@@ -54,6 +75,8 @@ pub struct Compiler {
     pub globals: Globals,
     pub cm: Arc<SourceMap>,
     handler: Handler,
+    config_cache: ConfigCache,
+    pub(crate) globals_cache: GlobalsCache,
 }
 
 impl Compiler {
@@ -62,68 +85,247 @@ impl Compiler {
             cm,
             handler,
             globals: Globals::new(),
+            config_cache: Default::default(),
+            globals_cache: Default::default(),
         }
     }
 
     /// Handles config merging.
+    ///
+    /// Resolved configs are cached per directory.
     pub(crate) fn config_for_file(
         &self,
         opts: &Options,
         fm: &SourceFile,
     ) -> Result<BuiltConfig, Error> {
-        let Options {
-            ref root,
-            root_mode,
-            swcrc,
+        let config = self.effective_config(opts, &fm.name)?;
+        Ok(opts.build(self, config))
+    }
+
+    /// Returns the merged config for `name`, with config files which
+    /// contributed to it and passes enabled by it.
+    pub(crate) fn load_options(
+        &self,
+        opts: &Options,
+        name: &FileName,
+    ) -> Result<LoadedOptions, Error> {
+        self.run(|| {
+            let config = self.effective_config(opts, name)?;
+            let files = config.files.clone();
+            let passes = opts
+                .build(self, config.clone())
+                .passes
+                .0
+                .into_iter()
+                .map(|(pass_name, _)| pass_name)
+                .collect();
+
+            Ok(LoadedOptions {
+                config,
+                files,
+                passes,
+            })
+        })
+    }
+
+    /// Returns a key which changes if the config for `name` changes.
+    pub(crate) fn get_cache_key(&self, opts: &Options, name: &FileName) -> Result<String, Error> {
+        let config = self.effective_config(opts, name)?;
+        cache_key(opts, &config)
+    }
+
+    /// Merges config files for `name` and the programmatic options.
+    fn effective_config(&self, opts: &Options, name: &FileName) -> Result<Config, Error> {
+        let key = CacheKey {
+            dir: real_path(name)
+                .and_then(Path::parent)
+                .map(Path::to_path_buf),
+            options: serde_json::to_string(&(
+                &opts.cwd,
+                &opts.root,
+                &opts.root_mode,
+                &opts.config_file,
+                opts.swcrc,
+                &opts.swcrc_roots,
+                opts.config.as_ref().and_then(|c| c.extends.as_ref()),
+            ))
+            .expect("failed to serialize options"),
+        };
+
+        let ConfigFiles {
             config_file,
-            ..
+            swcrc,
+            package_type,
+            extended,
+        } = self
+            .config_cache
+            .get_or_resolve(key, || self.resolve_config(opts, name))?;
+
+        // `test`, `include` and `exclude` of config files are matched against
+        // the absolute path.
+        let file = real_path(name)
+            .map(|path| PathBuf::from(clean(&opts.cwd.join(path).to_string_lossy())));
+        let file = file.as_ref().map(PathBuf::as_path);
+
+        let config_file = filter_config(config_file, file)?;
+        let mut config = match filter_config(swcrc, file)? {
+            Some(mut config) => {
+                if let Some(config_file) = config_file {
+                    config.merge(&config_file)
+                }
+                Some(config)
+            }
+            None => config_file,
+        };
+
+        // `type` of package.json is the default for `module`. Files are always
+        // parsed as es modules, and `"type": "module"` keeps them as is.
+        match package_type {
+            Some(PackageType::CommonJs) => {
+                let config = config.get_or_insert_with(Default::default);
+                if config.module.is_none() {
+                    config.module = Some(ModuleConfig::CommonJs(Default::default()));
+                }
+            }
+            Some(PackageType::Module) | None => {}
+        }
+
+        opts.effective_config(config, extended, file)
+    }
+
+    /// Reads and merges config files for `fm`.
+    fn resolve_config(&self, opts: &Options, name: &FileName) -> Result<Resolved, Error> {
+        let Options {
+            swcrc, config_file, ..
         } = opts;
-        let root = root
-            .clone()
-            .unwrap_or_else(|| ::std::env::current_dir().unwrap());
+        let mut deps = vec![];
+        let root = opts.root_dir(&mut deps)?;
+
+        let extended = match opts.config.as_ref().and_then(|c| c.extends.as_ref()) {
+            Some(spec) => Some(read_extended_config(&opts.cwd, spec, &mut deps)?),
+            None => None,
+        };
 
         let config_file = match config_file {
             Some(ConfigFile::Str(ref s)) => {
-                let path = Path::new(s);
-                let r = File::open(&path).map_err(|err| Error::FailedToReadConfigFile { err })?;
-                let config: Config = serde_json::from_reader(r)
-                    .map_err(|err| Error::FailedToParseConfigFile { err })?;
-                Some(config)
+                let path = PathBuf::from(clean(&opts.cwd.join(s).to_string_lossy()));
+                deps.push(path.clone());
+                Some(read_config_file(&path, &mut deps)?)
+            }
+            Some(ConfigFile::Bool(false)) => None,
+            Some(ConfigFile::Bool(true)) => {
+                let mut found = None;
+                for dir in &[&root, &opts.cwd] {
+                    let path = dir.join(ROOT_CONFIG_FILE);
+                    deps.push(path.clone());
+                    if path.is_file() {
+                        found = Some(read_config_file(&path, &mut deps)?);
+                        break;
+                    }
+                }
+                found
+            }
+            // A stray swc.config.json in the working directory should not
+            // affect every transform, so the default root config is only
+            // loaded if the root is given explicitly.
+            None if !opts.has_explicit_root() => None,
+            None => {
+                let path = root.join(ROOT_CONFIG_FILE);
+                deps.push(path.clone());
+                if path.is_file() {
+                    Some(read_config_file(&path, &mut deps)?)
+                } else {
+                    None
+                }
             }
-            _ => None,
         };
 
-        if *swcrc {
-            match fm.name {
-                FileName::Real(ref path) => {
-                    let mut parent = path.parent();
-                    while let Some(dir) = parent {
-                        let swcrc = dir.join(".swcrc");
-
-                        if swcrc.exists() {
-                            let r = File::open(&swcrc)
-                                .map_err(|err| Error::FailedToReadConfigFile { err })?;
-                            let mut config: Config = serde_json::from_reader(r)
-                                .map_err(|err| Error::FailedToParseConfigFile { err })?;
-                            if let Some(config_file) = config_file {
-                                config.merge(&config_file)
-                            }
-                            let built = opts.build(self, Some(config));
-                            return Ok(built);
-                        }
-
-                        if dir == root && *root_mode == RootMode::Root {
-                            break;
-                        }
-                        parent = dir.parent();
-                    }
+        let mut swcrc_config = None;
+        let mut package_type = None;
+
+        // `swcrc: false` disables all file-relative configs, including
+        // `package.json`.
+        if let Some(path) = real_path(name).filter(|_| *swcrc) {
+            let abs_path = PathBuf::from(clean(&opts.cwd.join(path).to_string_lossy()));
+            let pkg_dir = package_dir(&abs_path, &mut deps);
+
+            let pkg_json = pkg_dir
+                .map(|dir| dir.join("package.json"))
+                .filter(|path| path.is_file());
+            let pkg = match pkg_json {
+                Some(ref path) => {
+                    deps.push(path.clone());
+                    read_package_json(path, &mut deps)?
                 }
-                _ => {}
+                None => Default::default(),
+            };
+            package_type = pkg.module_type;
+
+            let allowed = match opts.swcrc_roots {
+                Some(ref swcrc_roots) => match pkg_dir {
+                    Some(pkg_dir) => swcrc_roots.allows(&opts.cwd, pkg_dir)?,
+                    None => false,
+                },
+                None => true,
+            };
+
+            // `.swcrc` takes precedence over `swc` of `package.json` in the
+            // same directory, and the search stops at the package boundary.
+            //
+            // If not allowed, only the root config is used.
+            let boundary = pkg_json.as_ref().and_then(|path| path.parent());
+            let mut pkg_swc = pkg.swc;
+            let mut parent = if allowed { abs_path.parent() } else { None };
+            while let Some(dir) = parent {
+                let swcrc = dir.join(".swcrc");
+                deps.push(swcrc.clone());
+
+                if swcrc.exists() {
+                    swcrc_config = Some(read_config_file(&swcrc, &mut deps)?);
+                    break;
+                }
+
+                if Some(dir) == boundary {
+                    swcrc_config = pkg_swc.take();
+                    break;
+                }
+
+                if dir == root {
+                    break;
+                }
+                parent = dir.parent();
             }
         }
 
-        let built = opts.build(self, config_file);
-        Ok(built)
+        Ok(Resolved {
+            files: ConfigFiles {
+                config_file,
+                swcrc: swcrc_config,
+                package_type,
+                extended,
+            },
+            deps,
+        })
+    }
+
+    /// Reads `path`, which is relative to `cwd`.
+    ///
+    /// The name of the source file is kept as is, so that relative paths in
+    /// source maps are relative to `cwd`.
+    pub(crate) fn load_file(&self, opts: &Options, path: &Path) -> Result<Arc<SourceFile>, Error> {
+        let src =
+            fs::read_to_string(opts.cwd.join(path)).map_err(|err| Error::FailedToReadModule {
+                path: path.into(),
+                err,
+            })?;
+
+        Ok(self.cm.new_source_file(FileName::Real(path.into()), src))
+    }
+
+    /// Forgets all resolved configs.
+    pub(crate) fn clear_config_cache(&self) {
+        self.config_cache.clear();
+        self.globals_cache.clear()
     }
 
     pub(crate) fn run<F, T>(&self, op: F) -> T
@@ -143,12 +345,130 @@ impl Compiler {
             handler: &self.handler,
         };
         let mut parser = Parser::new(session, syntax, SourceFileInput::from(&*fm), comments);
-        let module = parser.parse_module().map_err(|mut e| {
-            e.emit();
-            Error::FailedToParseModule {}
-        })?;
+        let (module, diagnostics) = diagnostics::collect("ERR_SWC_PARSE", || {
+            parser.parse_module().map_err(|mut e| {
+                e.emit();
+            })
+        });
+
+        module.map_err(|()| Error::FailedToParseModule { diagnostics })
+    }
+
+    /// Parses a module, skipping erroneous lines.
+    ///
+    /// As the parser can't recover from errors, an erroneous line is replaced
+    /// with whitespaces and the file is parsed again. Line and column numbers
+    /// are preserved. Skipped lines outside of other items are represented as
+    /// `EmptyStatement`s, and spans of all skipped lines are returned as
+    /// `skipped`, so that they can be told apart from `;` in the source.
+    ///
+    /// Each attempt parses the whole file, so the number of attempts is
+    /// limited by the size of the file, and at most `MAX_PARSED_BYTES` are
+    /// parsed in addition to the two attempts of giving up. If the file can't
+    /// be fixed this way, items before the erroneous part are returned.
+    pub(crate) fn parse_js_recovering(
+        &self,
+        fm: Arc<SourceFile>,
+        syntax: Syntax,
+        comments: Option<&Comments>,
+    ) -> Result<ParseOutput, Error> {
+        const MAX_ATTEMPTS: usize = 64;
+        const MAX_PARSED_BYTES: usize = 16 * 1024 * 1024;
+
+        let attempts = (MAX_PARSED_BYTES / fm.src.len().max(1))
+            .min(MAX_ATTEMPTS)
+            .max(1);
+        let mut src = fm.src.to_string();
+        let mut skipped = vec![];
+        let mut errors = vec![];
+        // Start of the part which could not be fixed.
+        let mut rest = 0;
+
+        for _ in 0..attempts {
+            let diagnostics = match self.parse_blanked(&fm, &src, syntax, comments) {
+                Ok(module) => return Ok(recovered(module, &fm, skipped, errors)),
+                Err(Error::FailedToParseModule { diagnostics }) => diagnostics,
+                Err(err) => unreachable!("parse_js returned unexpected error: {}", err),
+            };
 
-        Ok(module)
+            let line = diagnostics
+                .iter()
+                .filter_map(|d| d.span)
+                .map(|span| span.start.line)
+                .next();
+            errors.extend(diagnostics);
+
+            let range = match line.and_then(|line| erroneous_line(&src, line)) {
+                Some(range) => range,
+                None => {
+                    rest = 0;
+                    break;
+                }
+            };
+            rest = range.start;
+            blank(&mut src, range.clone());
+            skipped.push(range);
+        }
+
+        // Give up, and return items before the erroneous part.
+        for &start in &[rest, 0] {
+            let mut src = src.clone();
+            let range = start..src.len();
+            blank(&mut src, range.clone());
+
+            if let Ok(module) = self.parse_blanked(&fm, &src, syntax, comments) {
+                // Skipped lines are whole lines, so they are before or after `start`.
+                skipped.retain(|r| r.end <= start);
+                skipped.push(range);
+                return Ok(recovered(module, &fm, skipped, errors));
+            }
+        }
+
+        Err(Error::FailedToParseModule {
+            diagnostics: errors,
+        })
+    }
+
+    /// Parses `src`, which is `fm` with some parts replaced by whitespaces.
+    ///
+    /// Offsets are preserved, so spans point to `fm`, and the source map is
+    /// not modified.
+    fn parse_blanked(
+        &self,
+        fm: &SourceFile,
+        src: &str,
+        syntax: Syntax,
+        comments: Option<&Comments>,
+    ) -> Result<Module, Error> {
+        let blanked = SourceFile::new(
+            fm.name.clone(),
+            false,
+            fm.name.clone(),
+            src.to_string(),
+            fm.start_pos,
+        );
+
+        self.parse_js(Arc::new(blanked), syntax, comments)
+    }
+
+    pub(crate) fn parse_with_options(
+        &self,
+        fm: Arc<SourceFile>,
+        options: &ParseOptions,
+    ) -> Result<ParseOutput, Error> {
+        let comments = Default::default();
+        let comments = if options.comments {
+            Some(&comments)
+        } else {
+            None
+        };
+
+        if options.recover {
+            return self.parse_js_recovering(fm, options.syntax, comments);
+        }
+
+        self.parse_js(fm, options.syntax, comments)
+            .map(ParseOutput::Module)
     }
 
     pub(crate) fn process_js_file(
@@ -157,27 +477,67 @@ impl Compiler {
         opts: Options,
     ) -> Result<TransformOutput, Error> {
         self.run(|| {
-            if error::debug() {
-                eprintln!("processing js file: {:?}", fm)
-            }
-
-            let config = self.config_for_file(&opts, &*fm)?;
-
-            let comments = Default::default();
-            let module = self.parse_js(
-                fm.clone(),
-                config.syntax,
-                if config.minify { None } else { Some(&comments) },
-            )?;
-            let mut pass = config.pass;
-            let module = helpers::HELPERS.set(&Helpers::new(config.external_helpers), || {
-                util::HANDLER.set(&self.handler, || {
-                    // Fold module
-                    module.fold_with(&mut pass)
-                })
+            let mut timings = Timings::new();
+
+            let (output, diagnostics) = diagnostics::collect("ERR_SWC_TRANSFORM", || {
+                let BuiltConfig {
+                    passes,
+                    syntax,
+                    minify,
+                    external_helpers,
+                    source_maps,
+                } = timings.time("resolveConfig", || self.config_for_file(&opts, &*fm))?;
+
+                let comments = Default::default();
+                let module = timings.time("parse", || {
+                    self.parse_js(
+                        fm.clone(),
+                        syntax,
+                        if minify { None } else { Some(&comments) },
+                    )
+                })?;
+                let module = helpers::HELPERS.set(&Helpers::new(external_helpers), || {
+                    util::HANDLER.set(&self.handler, || {
+                        // Fold module
+                        passes
+                            .0
+                            .into_iter()
+                            .fold(module, |module, (name, mut pass)| {
+                                timings
+                                    .time(&format!("pass:{}", name), || module.fold_with(&mut pass))
+                            })
+                    })
+                });
+
+                self.print(
+                    &module,
+                    fm.clone(),
+                    &comments,
+                    source_maps,
+                    minify,
+                    &mut timings,
+                )
             });
 
-            self.print(&module, fm, &comments, config.source_maps, config.minify)
+            let mut output = output?;
+            output.warnings = diagnostics
+                .into_iter()
+                .filter(|d| d.severity < Severity::Error)
+                .collect();
+
+            if let Some(ref path) = opts.trace_file {
+                timings
+                    .write_chrome_trace(&opts.cwd.join(path), &fm.name.to_string())
+                    .map_err(|err| Error::FailedToWriteTrace {
+                        path: path.clone(),
+                        err,
+                    })?;
+            }
+            if opts.timings {
+                output.timings = Some(timings.into_phases());
+            }
+
+            Ok(output)
         })
     }
 
@@ -188,6 +548,7 @@ impl Compiler {
         comments: &Comments,
         source_map: bool,
         minify: bool,
+        timings: &mut Timings,
     ) -> Result<TransformOutput, Error> {
         self.run(|| {
             let mut src_map_builder = SourceMapBuilder::new(None);
@@ -200,7 +561,7 @@ impl Compiler {
                 _ => {}
             }
 
-            let src = {
+            let src = timings.time("codegen", || -> Result<_, Error> {
                 let mut buf = vec![];
                 {
                     let handlers = box MyHandlers;
@@ -227,11 +588,10 @@ impl Compiler {
                         .map_err(|err| Error::FailedToEmitModule { err })?;
                 }
                 // Invalid utf8 is valid in javascript world.
-                unsafe { String::from_utf8_unchecked(buf) }
-            };
-            Ok(TransformOutput {
-                code: src,
-                map: if source_map {
+                Ok(unsafe { String::from_utf8_unchecked(buf) })
+            })?;
+            let map = if source_map {
+                timings.time("sourceMap", || -> Result<_, Error> {
                     let mut buf = vec![];
                     src_map_builder
                         .into_sourcemap()
@@ -239,27 +599,125 @@ impl Compiler {
                         .map_err(|err| Error::FailedToWriteSourceMap { err })?;
                     let map =
                         String::from_utf8(buf).map_err(|err| Error::SourceMapNotUtf8 { err })?;
-                    Some(map)
-                } else {
-                    None
-                },
+                    Ok(Some(map))
+                })?
+            } else {
+                None
+            };
+
+            Ok(TransformOutput {
+                code: src,
+                map,
+                warnings: vec![],
+                timings: None,
             })
         })
     }
 }
 
+/// Returns byte range of `line` (1-based), or the last non-empty line
+/// before it if `line` is empty.
+fn erroneous_line(src: &str, line: usize) -> Option<Range<usize>> {
+    let mut start = 0;
+    let mut candidate = None;
+
+    for (idx, l) in src.split('\n').enumerate() {
+        if idx >= line {
+            break;
+        }
+        if !l.trim().is_empty() {
+            candidate = Some(start..start + l.len());
+        }
+        start += l.len() + 1;
+    }
+
+    candidate
+}
+
+/// Replaces characters in `range` with whitespaces, while preserving byte
+/// offsets and line breaks.
+fn blank(src: &mut String, range: Range<usize>) {
+    let blanked = src[range.clone()]
+        .chars()
+        .map(|c| match c {
+            '\n' | '\r' => c.to_string(),
+            _ => " ".repeat(c.len_utf8()),
+        })
+        .collect::<String>();
+
+    src.replace_range(range, &blanked);
+}
+
+/// Inserts `EmptyStatement`s for `skipped` byte ranges of `fm`, and returns
+/// them with their spans.
+fn recovered(
+    mut module: Module,
+    fm: &SourceFile,
+    skipped: Vec<Range<usize>>,
+    diagnostics: Vec<Diagnostic>,
+) -> ParseOutput {
+    let mut spans: Vec<_> = skipped
+        .into_iter()
+        .map(|range| {
+            Span::new(
+                fm.start_pos + BytePos(range.start as u32),
+                fm.start_pos + BytePos(range.end as u32),
+                SyntaxContext::empty(),
+            )
+        })
+        .collect();
+    spans.sort_by_key(|span| span.lo());
+
+    for &span in &spans {
+        insert_error_node(&mut module, span);
+    }
+
+    ParseOutput::Recovered {
+        module,
+        skipped: spans,
+        diagnostics,
+    }
+}
+
+/// Inserts an `EmptyStatement` for `span`, if it's not a part of other items.
+fn insert_error_node(module: &mut Module, span: Span) {
+    let contained = module.body.iter().any(|item| {
+        let s = item.span();
+        s.lo() <= span.lo() && span.hi() <= s.hi()
+    });
+    if contained {
+        return;
+    }
+
+    let idx = module
+        .body
+        .iter()
+        .position(|item| item.span().lo() >= span.hi())
+        .unwrap_or_else(|| module.body.len());
+    module
+        .body
+        .insert(idx, ModuleItem::Stmt(Stmt::Empty(EmptyStmt { span })));
+}
+
 struct MyHandlers;
 
 impl swc::ecmascript::codegen::Handlers for MyHandlers {}
 
-fn init(_cx: MethodContext<JsUndefined>) -> NeonResult<ArcCompiler> {
+fn init(mut cx: MethodContext<JsUndefined>) -> NeonResult<ArcCompiler> {
+    let options: CompilerOptions = match cx.argument_opt(0) {
+        Some(v) => neon_serde::from_value(&mut cx, v)?,
+        None => {
+            let obj = cx.empty_object().upcast();
+            neon_serde::from_value(&mut cx, obj)?
+        }
+    };
+
     let cm = Arc::new(SourceMap::new(FilePathMapping::empty()));
 
-    let handler = Handler::with_tty_emitter(
-        common::errors::ColorConfig::Always,
+    let handler = Handler::with_emitter(
         true,
         false,
-        Some(cm.clone()),
+        box CollectingEmitter::new(cm.clone(), options.code_frame(), options.diagnostics_format),
     );
 
     let c = Compiler::new(cm.clone(), handler);
@@ -279,19 +737,60 @@ struct TransformFileTask {
     options: Options,
 }
 
+/// Result of `loadOptions()`.
+#[derive(Serialize)]
+pub(crate) struct LoadedOptions {
+    config: Config,
+    /// Config files merged into `config`, from the lowest precedence.
+    files: Vec<PathBuf>,
+    /// Names of enabled passes, in order.
+    passes: Vec<&'static str>,
+}
+
 #[derive(Serialize)]
 struct TransformOutput {
     code: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     map: Option<String>,
+    /// Diagnostics below error level.
+    warnings: Vec<Diagnostic>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timings: Option<Vec<Phase>>,
 }
 
 impl TransformOutput {
-    fn complete(mut cx: TaskContext, result: Result<TransformOutput, Error>) -> JsResult<JsValue> {
+    fn complete(
+        mut cx: TaskContext,
+        result: Result<TransformOutput, Error>,
+        filename: Option<&Path>,
+    ) -> JsResult<JsValue> {
         match result {
             Ok(output) => Ok(neon_serde::to_value(&mut cx, &output)?),
-            Err(err) => cx.throw_error(err.to_string()),
+            Err(err) => err.throw(&mut cx, filename),
+        }
+    }
+}
+
+/// Returns `None` if `test`, `include` or `exclude` of the config file excludes
+/// `file`.
+fn filter_config(config: Option<Config>, file: Option<&Path>) -> Result<Option<Config>, Error> {
+    match config {
+        Some(config) => {
+            if config.matches(file)? {
+                Ok(Some(config))
+            } else {
+                Ok(None)
+            }
         }
+        None => Ok(None),
+    }
+}
+
+/// Returns the path of a file, if it's a real file.
+fn real_path(name: &FileName) -> Option<&Path> {
+    match *name {
+        FileName::Real(ref path) => Some(path),
+        _ => None,
     }
 }
 
@@ -301,8 +800,10 @@ impl Task for TransformTask {
     type JsEvent = JsValue;
 
     fn perform(&self) -> Result<Self::Output, Self::Error> {
-        self.c
-            .process_js_file(self.fm.clone(), self.options.clone())
+        catch_panic(|| {
+            self.c
+                .process_js_file(self.fm.clone(), self.options.clone())
+        })
     }
 
     fn complete(
@@ -310,7 +811,7 @@ impl Task for TransformTask {
         cx: TaskContext,
         result: Result<Self::Output, Self::Error>,
     ) -> JsResult<Self::JsEvent> {
-        TransformOutput::complete(cx, result)
+        TransformOutput::complete(cx, result, real_path(&self.fm.name))
     }
 }
 
@@ -320,13 +821,11 @@ impl Task for TransformFileTask {
     type JsEvent = JsValue;
 
     fn perform(&self) -> Result<Self::Output, Self::Error> {
-        let fm = self
-            .c
-            .cm
-            .load_file(&self.path)
-            .map_err(|err| Error::FailedToReadModule { err })?;
+        catch_panic(|| {
+            let fm = self.c.load_file(&self.options, &self.path)?;
 
-        self.c.process_js_file(fm, self.options.clone())
+            self.c.process_js_file(fm, self.options.clone())
+        })
     }
 
     fn complete(
@@ -334,7 +833,7 @@ impl Task for TransformFileTask {
         cx: TaskContext,
         result: Result<Self::Output, Self::Error>,
     ) -> JsResult<Self::JsEvent> {
-        TransformOutput::complete(cx, result)
+        TransformOutput::complete(cx, result, Some(&self.path))
     }
 }
 
@@ -391,8 +890,15 @@ fn transform_sync(mut cx: MethodContext<JsCompiler>) -> JsResult<JsValue> {
             source.value(),
         );
 
-        c.process_js_file(fm, options)
-            .expect("failed to process js file")
+        let filename = real_path(&fm.name).map(PathBuf::from);
+
+        (catch_panic(|| c.process_js_file(fm, options)), filename)
+    };
+    let output = match output {
+        (Ok(v), _) => v,
+        (Err(err), filename) => {
+            return err.throw(&mut cx, filename.as_ref().map(PathBuf::as_path));
+        }
     };
 
     Ok(neon_serde::to_value(&mut cx, &output)?)
@@ -401,6 +907,7 @@ fn transform_sync(mut cx: MethodContext<JsCompiler>) -> JsResult<JsValue> {
 fn transform_file(mut cx: MethodContext<JsCompiler>) -> JsResult<JsValue> {
     let path = cx.argument::<JsString>(0)?;
     let path_value = path.value();
+    let path_value = clean(&path_value);
     let path = Path::new(&path_value);
 
     let options_arg = cx.argument::<JsValue>(1)?;
@@ -441,9 +948,68 @@ fn transform_file_sync(mut cx: MethodContext<JsCompiler>) -> JsResult<JsValue> {
     let output = {
         let guard = cx.lock();
         let c = this.borrow(&guard);
-        let fm = c.cm.load_file(path).expect("failed to load file");
-        c.process_js_file(fm, opts)
-            .expect("failed to process js file")
+        c.load_file(&opts, path)
+            .and_then(|fm| catch_panic(|| c.process_js_file(fm, opts)))
+    };
+    let output = match output {
+        Ok(v) => v,
+        Err(err) => return err.throw(&mut cx, Some(path)),
+    };
+
+    Ok(neon_serde::to_value(&mut cx, &output)?)
+}
+
+fn get_cache_key_sync(mut cx: MethodContext<JsCompiler>) -> JsResult<JsValue> {
+    let path = cx.argument::<JsString>(0)?;
+    let opts: Options = match cx.argument_opt(1) {
+        Some(v) => neon_serde::from_value(&mut cx, v)?,
+        None => {
+            let obj = cx.empty_object().upcast();
+            neon_serde::from_value(&mut cx, obj)?
+        }
+    };
+
+    let path_value = path.value();
+    let path_value = clean(&path_value);
+    let path = Path::new(&path_value);
+
+    let this = cx.this();
+    let output = {
+        let guard = cx.lock();
+        let c = this.borrow(&guard);
+        catch_panic(|| c.get_cache_key(&opts, &FileName::Real(path.into())))
+    };
+    let output = match output {
+        Ok(v) => v,
+        Err(err) => return err.throw(&mut cx, Some(path)),
+    };
+
+    Ok(cx.string(output).upcast())
+}
+
+fn load_options_sync(mut cx: MethodContext<JsCompiler>) -> JsResult<JsValue> {
+    let path = cx.argument::<JsString>(0)?;
+    let opts: Options = match cx.argument_opt(1) {
+        Some(v) => neon_serde::from_value(&mut cx, v)?,
+        None => {
+            let obj = cx.empty_object().upcast();
+            neon_serde::from_value(&mut cx, obj)?
+        }
+    };
+
+    let path_value = path.value();
+    let path_value = clean(&path_value);
+    let path = Path::new(&path_value);
+
+    let this = cx.this();
+    let output = {
+        let guard = cx.lock();
+        let c = this.borrow(&guard);
+        catch_panic(|| c.load_options(&opts, &FileName::Real(path.into())))
+    };
+    let output = match output {
+        Ok(v) => v,
+        Err(err) => return err.throw(&mut cx, Some(path)),
     };
 
     Ok(neon_serde::to_value(&mut cx, &output)?)
@@ -463,30 +1029,37 @@ struct ParseFileTask {
     options: ParseOptions,
 }
 
-fn complete_parse(mut cx: TaskContext, result: Result<Module, Error>) -> JsResult<JsValue> {
+#[derive(Serialize)]
+#[serde(untagged)]
+pub(crate) enum ParseOutput {
+    Module(Module),
+    /// Output of the recovery mode.
+    Recovered {
+        module: Module,
+        /// Spans of skipped lines, sorted.
+        skipped: Vec<Span>,
+        diagnostics: Vec<Diagnostic>,
+    },
+}
+
+fn complete_parse(
+    mut cx: TaskContext,
+    result: Result<ParseOutput, Error>,
+    filename: Option<&Path>,
+) -> JsResult<JsValue> {
     match result {
         Ok(module) => Ok(neon_serde::to_value(&mut cx, &module)?),
-        Err(err) => cx.throw_error(err.to_string()),
+        Err(err) => err.throw(&mut cx, filename),
     }
 }
 
 impl Task for ParseTask {
-    type Output = Module;
+    type Output = ParseOutput;
     type Error = Error;
     type JsEvent = JsValue;
 
     fn perform(&self) -> Result<Self::Output, Self::Error> {
-        let comments = Default::default();
-
-        self.c.parse_js(
-            self.fm.clone(),
-            self.options.syntax,
-            if self.options.comments {
-                Some(&comments)
-            } else {
-                None
-            },
-        )
+        catch_panic(|| self.c.parse_with_options(self.fm.clone(), &self.options))
     }
 
     fn complete(
@@ -494,32 +1067,28 @@ impl Task for ParseTask {
         cx: TaskContext,
         result: Result<Self::Output, Self::Error>,
     ) -> JsResult<Self::JsEvent> {
-        complete_parse(cx, result)
+        complete_parse(cx, result, None)
     }
 }
 
 impl Task for ParseFileTask {
-    type Output = Module;
+    type Output = ParseOutput;
     type Error = Error;
     type JsEvent = JsValue;
 
     fn perform(&self) -> Result<Self::Output, Self::Error> {
-        let comments = Default::default();
-        let fm = self
-            .c
-            .cm
-            .load_file(&self.path)
-            .map_err(|err| Error::FailedToReadModule { err })?;
-
-        self.c.parse_js(
-            fm,
-            self.options.syntax,
-            if self.options.comments {
-                Some(&comments)
-            } else {
-                None
-            },
-        )
+        catch_panic(|| {
+            let fm = self
+                .c
+                .cm
+                .load_file(&self.path)
+                .map_err(|err| Error::FailedToReadModule {
+                    path: self.path.clone(),
+                    err,
+                })?;
+
+            self.c.parse_with_options(fm, &self.options)
+        })
     }
 
     fn complete(
@@ -527,7 +1096,7 @@ impl Task for ParseFileTask {
         cx: TaskContext,
         result: Result<Self::Output, Self::Error>,
     ) -> JsResult<Self::JsEvent> {
-        complete_parse(cx, result)
+        complete_parse(cx, result, Some(&self.path))
     }
 }
 
@@ -566,21 +1135,12 @@ fn parse_sync(mut cx: MethodContext<JsCompiler>) -> JsResult<JsValue> {
         let c = this.borrow(&guard);
 
         let fm = c.cm.new_source_file(FileName::Anon, src.value());
-        let comments = Default::default();
 
-        c.parse_js(
-            fm,
-            options.syntax,
-            if options.comments {
-                Some(&comments)
-            } else {
-                None
-            },
-        )
+        catch_panic(|| c.parse_with_options(fm, &options))
     };
     let module = match module {
         Ok(v) => v,
-        Err(err) => return cx.throw_error(err.to_string()),
+        Err(err) => return err.throw(&mut cx, None),
     };
 
     Ok(neon_serde::to_value(&mut cx, &module)?)
@@ -591,29 +1151,24 @@ fn parse_file_sync(mut cx: MethodContext<JsCompiler>) -> JsResult<JsValue> {
     let options_arg = cx.argument::<JsValue>(1)?;
     let options: ParseOptions = neon_serde::from_value(&mut cx, options_arg)?;
 
+    let path_value = path.value();
+    let path = Path::new(&path_value);
+
     let this = cx.this();
     let module = {
         let guard = cx.lock();
         let c = this.borrow(&guard);
 
-        let fm =
-            c.cm.load_file(Path::new(&path.value()))
-                .expect("failed to read module file");
-        let comments = Default::default();
-
-        c.parse_js(
-            fm,
-            options.syntax,
-            if options.comments {
-                Some(&comments)
-            } else {
-                None
-            },
-        )
+        c.cm.load_file(path)
+            .map_err(|err| Error::FailedToReadModule {
+                path: path.into(),
+                err,
+            })
+            .and_then(|fm| catch_panic(|| c.parse_with_options(fm, &options)))
     };
     let module = match module {
         Ok(v) => v,
-        Err(err) => return cx.throw_error(err.to_string()),
+        Err(err) => return err.throw(&mut cx, Some(path)),
     };
 
     Ok(neon_serde::to_value(&mut cx, &module)?)
@@ -654,22 +1209,25 @@ impl Task for PrintTask {
     type Error = Error;
     type JsEvent = JsValue;
     fn perform(&self) -> Result<Self::Output, Self::Error> {
-        let loc = self.c.cm.lookup_char_pos(self.module.span().lo());
-        let fm = loc.file;
-        let comments = Default::default();
+        catch_panic(|| {
+            let loc = self.c.cm.lookup_char_pos(self.module.span().lo());
+            let fm = loc.file;
+            let comments = Default::default();
 
-        self.c.print(
-            &self.module,
-            fm,
-            &comments,
-            self.options.source_maps.is_some(),
-            self.options
-                .config
-                .clone()
-                .unwrap_or_default()
-                .minify
-                .unwrap_or(false),
-        )
+            self.c.print(
+                &self.module,
+                fm,
+                &comments,
+                self.options.source_maps.is_some(),
+                self.options
+                    .config
+                    .clone()
+                    .unwrap_or_default()
+                    .minify
+                    .unwrap_or(false),
+                &mut Timings::new(),
+            )
+        })
     }
 
     fn complete(
@@ -677,7 +1235,9 @@ impl Task for PrintTask {
         cx: TaskContext,
         result: Result<Self::Output, Self::Error>,
     ) -> JsResult<Self::JsEvent> {
-        TransformOutput::complete(cx, result)
+        let fm = self.c.cm.lookup_char_pos(self.module.span().lo()).file;
+
+        TransformOutput::complete(cx, result, real_path(&fm.name))
     }
 }
 
@@ -719,19 +1279,28 @@ fn print_sync(mut cx: MethodContext<JsCompiler>) -> JsResult<JsValue> {
         let c = this.borrow(&guard);
         let loc = c.cm.lookup_char_pos(module.span().lo());
         let fm = loc.file;
+        let filename = real_path(&fm.name).map(PathBuf::from);
         let comments = Default::default();
 
-        c.print(
-            &module,
-            fm,
-            &comments,
-            options.source_maps.is_some(),
-            options.config.unwrap_or_default().minify.unwrap_or(false),
+        (
+            catch_panic(|| {
+                c.print(
+                    &module,
+                    fm,
+                    &comments,
+                    options.source_maps.is_some(),
+                    options.config.unwrap_or_default().minify.unwrap_or(false),
+                    &mut Timings::new(),
+                )
+            }),
+            filename,
         )
     };
     let result = match result {
-        Ok(v) => v,
-        Err(err) => return cx.throw_error(err.to_string()),
+        (Ok(v), _) => v,
+        (Err(err), filename) => {
+            return err.throw(&mut cx, filename.as_ref().map(PathBuf::as_path));
+        }
     };
 
     Ok(neon_serde::to_value(&mut cx, &result)?)
@@ -784,10 +1353,42 @@ declare_types! {
         method printSync(cx) {
             print_sync(cx)
         }
+
+        method loadOptions(cx) {
+            load_options_sync(cx)
+        }
+
+        method getCacheKey(cx) {
+            get_cache_key_sync(cx)
+        }
+
+        method clearConfigCache(mut cx) {
+            let this = cx.this();
+            {
+                let guard = cx.lock();
+                let c = this.borrow(&guard);
+                c.clear_config_cache();
+            }
+
+            Ok(cx.undefined().upcast())
+        }
     }
 }
 
+/// Returns the JSON schema of `.swcrc`.
+fn config_schema(mut cx: FunctionContext) -> JsResult<JsValue> {
+    Ok(neon_serde::to_value(&mut cx, &schema::config_schema())?)
+}
+
 register_module!(mut cx, {
     cx.export_class::<JsCompiler>("Compiler")?;
+    cx.export_function("configSchema", config_schema)?;
+
+    let codes = JsArray::new(&mut cx, error::CODES.len() as u32);
+    for (i, code) in error::CODES.iter().enumerate() {
+        let code = cx.string(code);
+        codes.set(&mut cx, i as u32, code)?;
+    }
+    cx.export_value("codes", codes)?;
     Ok(())
 });
@@ -0,0 +1,106 @@
+//! JSON schema of `.swcrc`.
+//!
+//! Types of swc do not implement `JsonSchema`, so they are mirrored here.
+//! Keep them in sync with the types of swc. `__tests__/schema_test.js` checks
+//! that they accept and produce the same json.
+
+use crate::config::Config;
+use schemars::JsonSchema;
+use std::collections::HashMap;
+
+/// Returns the JSON schema of `.swcrc`.
+pub(crate) fn config_schema() -> serde_json::Value {
+    serde_json::to_value(schemars::schema_for!(Config)).expect("failed to serialize schema")
+}
+
+/// `swc::ecmascript::parser::Syntax`
+#[derive(JsonSchema)]
+#[serde(tag = "syntax")]
+pub(crate) enum Syntax {
+    #[serde(rename = "ecmascript")]
+    Es(EsConfig),
+    #[serde(rename = "typescript")]
+    Typescript(TsConfig),
+}
+
+/// `swc::ecmascript::parser::EsConfig`
+#[derive(JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub(crate) struct EsConfig {
+    #[serde(default)]
+    pub jsx: bool,
+    #[serde(default)]
+    pub numeric_separator: bool,
+    #[serde(default)]
+    pub class_private_property: bool,
+    #[serde(default)]
+    pub private_method: bool,
+    #[serde(default)]
+    pub class_property: bool,
+    #[serde(default)]
+    pub function_bind: bool,
+    #[serde(default)]
+    pub decorators: bool,
+    #[serde(default)]
+    pub decorators_before_export: bool,
+    #[serde(default)]
+    pub export_default_from: bool,
+    #[serde(default)]
+    pub export_namespace_from: bool,
+    #[serde(default)]
+    pub dynamic_import: bool,
+}
+
+/// `swc::ecmascript::parser::TsConfig`
+#[derive(JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub(crate) struct TsConfig {
+    #[serde(default)]
+    pub tsx: bool,
+    #[serde(default)]
+    pub decorators: bool,
+    #[serde(default)]
+    pub dynamic_import: bool,
+}
+
+/// `modules::common_js::Config`
+#[derive(JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub(crate) struct CommonJsConfig {
+    #[serde(default)]
+    pub strict: bool,
+    #[serde(default)]
+    pub strict_mode: bool,
+    #[serde(default)]
+    pub lazy: Option<Lazy>,
+    #[serde(default)]
+    pub no_interop: bool,
+}
+
+/// `modules::util::Lazy`
+#[derive(JsonSchema)]
+#[serde(untagged)]
+pub(crate) enum Lazy {
+    Bool(bool),
+    List(Vec<String>),
+}
+
+/// `modules::umd::Config`
+#[derive(JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub(crate) struct UmdConfig {
+    #[serde(default)]
+    pub globals: HashMap<String, String>,
+    #[serde(flatten)]
+    pub config: CommonJsConfig,
+}
+
+/// `modules::amd::Config`
+#[derive(JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub(crate) struct AmdConfig {
+    #[serde(default)]
+    pub module_id: Option<String>,
+    #[serde(flatten)]
+    pub config: CommonJsConfig,
+}
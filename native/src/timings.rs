@@ -0,0 +1,129 @@
+use serde::Serialize;
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+    time::{Duration, Instant},
+};
+
+/// Records how long each phase of a transform takes.
+pub(crate) struct Timings {
+    start: Instant,
+    phases: Vec<Phase>,
+}
+
+/// A timed phase, like `parse` or `pass:hygiene`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Phase {
+    pub name: String,
+    /// Milliseconds since the start of the transform.
+    pub start: f64,
+    /// Milliseconds.
+    pub duration: f64,
+}
+
+impl Timings {
+    pub fn new() -> Self {
+        Timings {
+            start: Instant::now(),
+            phases: vec![],
+        }
+    }
+
+    /// Invokes `op` and records its duration as `name`.
+    pub fn time<F, T>(&mut self, name: &str, op: F) -> T
+    where
+        F: FnOnce() -> T,
+    {
+        let start = Instant::now();
+        let ret = op();
+        let end = Instant::now();
+
+        self.phases.push(Phase {
+            name: name.to_string(),
+            start: as_millis(start - self.start),
+            duration: as_millis(end - start),
+        });
+
+        ret
+    }
+
+    pub fn into_phases(self) -> Vec<Phase> {
+        self.phases
+    }
+
+    /// Writes phases in the trace event format, which can be loaded with
+    /// `chrome://tracing`.
+    pub fn write_chrome_trace(&self, path: &Path, filename: &str) -> io::Result<()> {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Trace<'a> {
+            trace_events: Vec<Event<'a>>,
+            display_time_unit: &'static str,
+        }
+
+        #[derive(Serialize)]
+        struct Event<'a> {
+            name: &'a str,
+            cat: &'static str,
+            ph: &'static str,
+            /// Microseconds.
+            ts: f64,
+            /// Microseconds.
+            dur: f64,
+            pid: u32,
+            tid: u32,
+            args: Args<'a>,
+        }
+
+        #[derive(Serialize)]
+        struct Args<'a> {
+            filename: &'a str,
+        }
+
+        let total = self
+            .phases
+            .iter()
+            .map(|p| p.start + p.duration)
+            .fold(0.0, f64::max);
+
+        let trace_events = Some(Event {
+            name: "transform",
+            cat: "swc",
+            ph: "X",
+            ts: 0.0,
+            dur: total * 1000.0,
+            pid: 1,
+            tid: 1,
+            args: Args { filename },
+        })
+        .into_iter()
+        .chain(self.phases.iter().map(|p| Event {
+            name: &p.name,
+            cat: "swc",
+            ph: "X",
+            ts: p.start * 1000.0,
+            dur: p.duration * 1000.0,
+            pid: 1,
+            tid: 1,
+            args: Args { filename },
+        }))
+        .collect();
+
+        let mut w = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(
+            &mut w,
+            &Trace {
+                trace_events,
+                display_time_unit: "ms",
+            },
+        )?;
+
+        w.flush()
+    }
+}
+
+fn as_millis(d: Duration) -> f64 {
+    d.as_secs() as f64 * 1000.0 + f64::from(d.subsec_nanos()) / 1_000_000.0
+}
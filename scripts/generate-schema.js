@@ -0,0 +1,25 @@
+/**
+ * Writes the JSON schema of `.swcrc`, generated from the types of the native
+ * module, to `lib/swcrc.schema.json`.
+ *
+ * The schema is committed, so run this after changing the config types.
+ */
+const fs = require('fs');
+const path = require('path');
+
+const file = path.resolve(__dirname, '../lib/swcrc.schema.json');
+
+/**
+ * Returns the content of `lib/swcrc.schema.json`.
+ */
+function render() {
+    const { configSchema } = require('../native');
+
+    return JSON.stringify(configSchema(), null, 2) + '\n';
+}
+
+module.exports = { file, render };
+
+if (require.main === module) {
+    fs.writeFileSync(file, render());
+}